@@ -0,0 +1,28 @@
+// Write every payload schema to `<dir>/<Type>.schema.json` (default: ./schemas)
+//
+//   cargo run -p ivlyrics-protocol --features schema --bin dump-schemas -- <dir>
+//   npm run schemas   (writes ./schemas from the repo root)
+
+use std::path::PathBuf;
+
+fn main() -> Result<(), String> {
+    let out_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("schemas"));
+
+    std::fs::create_dir_all(&out_dir)
+        .map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+
+    for name in ivlyrics_protocol::SCHEMA_TYPES {
+        let schema = ivlyrics_protocol::schema_for(name)
+            .ok_or_else(|| format!("No schema for {}", name))?;
+        let json = serde_json::to_string_pretty(&schema).map_err(|e| e.to_string())?;
+        let path = out_dir.join(format!("{}.schema.json", name));
+        std::fs::write(&path, json + "\n")
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
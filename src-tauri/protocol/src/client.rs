@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{discover_port, LyricsData, ProgressData, TrackInfo};
+
+/// Async sender for the overlay's local HTTP server.
+///
+/// Connection failures and server errors are retried with exponential backoff;
+/// the last error is returned once the retries run out.
+#[derive(Debug, Clone)]
+pub struct OverlayClient {
+    http: reqwest::Client,
+    base_url: String,
+    access_token: Option<String>,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+impl OverlayClient {
+    /// Client for an overlay at `base_url`, e.g. `http://127.0.0.1:15000`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            access_token: None,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(200),
+        }
+    }
+
+    /// Client for the overlay on this machine, using `discover_port`.
+    pub fn discover() -> Self {
+        Self::new(format!("http://127.0.0.1:{}", discover_port()))
+    }
+
+    /// Token sent as `Authorization: Bearer`, needed when the overlay runs in LAN mode.
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        let token = token.into();
+        self.access_token = (!token.is_empty()).then_some(token);
+        self
+    }
+
+    /// Number of retries after the first failed attempt (default 3).
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry; doubled on every further retry (default 200ms).
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// POST a full lyrics payload to `/lyrics`.
+    pub async fn send_lyrics(&self, lyrics: &LyricsData) -> Result<(), String> {
+        self.post("/lyrics", lyrics).await
+    }
+
+    /// POST only the track to `/lyrics/ref`. `Ok(false)` means the overlay has no
+    /// cached lyrics for it and the full payload has to be sent.
+    pub async fn send_lyrics_ref(&self, track: &TrackInfo) -> Result<bool, String> {
+        match self.post_status("/lyrics/ref", track).await? {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => Err(format!("{}/lyrics/ref returned {}", self.base_url, status)),
+        }
+    }
+
+    /// Send lyrics by reference when the overlay has them cached, in full otherwise.
+    pub async fn send_lyrics_cached(&self, lyrics: &LyricsData) -> Result<(), String> {
+        if self.send_lyrics_ref(&lyrics.track).await? {
+            return Ok(());
+        }
+        self.send_lyrics(lyrics).await
+    }
+
+    /// POST a playback position update to `/progress`.
+    pub async fn send_progress(&self, progress: &ProgressData) -> Result<(), String> {
+        self.post("/progress", progress).await
+    }
+
+    async fn post<T: Serialize + ?Sized>(&self, path: &str, body: &T) -> Result<(), String> {
+        let status = self.post_status(path, body).await?;
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(format!("{}{} returned {}", self.base_url, path, status))
+        }
+    }
+
+    // Final status of a POST; connection failures and server errors are retried,
+    // other statuses are returned as-is since a retry would get the same answer
+    async fn post_status<T: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::StatusCode, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+
+        loop {
+            let mut request = self.http.post(&url).json(body);
+            if let Some(token) = &self.access_token {
+                request = request.bearer_auth(token);
+            }
+
+            let result = match request.send().await {
+                Ok(response) if !response.status().is_server_error() => {
+                    return Ok(response.status());
+                }
+                Ok(response) => Err(format!("{} returned {}", url, response.status())),
+                Err(e) => Err(format!("Failed to reach {}: {}", url, e)),
+            };
+
+            if attempt >= self.max_retries {
+                return result;
+            }
+            attempt += 1;
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+/// Port the overlay listens on when nothing else is configured.
+pub const DEFAULT_PORT: u16 = 15000;
+
+/// The overlay's config directory (`<config>/ivlyrics-overlay`), whether or not it exists yet.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("ivlyrics-overlay"))
+}
+
+/// Resolve the overlay port the same way the overlay does:
+/// `IVLYRICS_PORT`, then `port.txt` in the config directory, then `DEFAULT_PORT`.
+/// Ports below 1024 are ignored.
+pub fn discover_port() -> u16 {
+    // Try environment variable first
+    if let Ok(port_str) = std::env::var("IVLYRICS_PORT") {
+        if let Ok(port) = port_str.trim().parse::<u16>() {
+            if port >= 1024 {
+                return port;
+            }
+        }
+    }
+
+    // Try config file
+    if let Some(dir) = config_dir() {
+        if let Ok(content) = std::fs::read_to_string(dir.join("port.txt")) {
+            if let Ok(port) = content.trim().parse::<u16>() {
+                if port >= 1024 {
+                    return port;
+                }
+            }
+        }
+    }
+
+    DEFAULT_PORT
+}
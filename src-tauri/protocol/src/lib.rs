@@ -0,0 +1,175 @@
+//! Payload types shared by the Lyrics Plus Overlay and the senders that feed it.
+//!
+//! Senders POST `LyricsData` to `/lyrics` and `ProgressData` to `/progress` on the
+//! overlay's local server. Field names are camelCase on the wire. For repeat plays a
+//! sender can POST just the `TrackInfo` to `/lyrics/ref`; a 404 means the overlay has
+//! nothing cached and the full `LyricsData` is needed.
+//! Enable the `client` feature for a ready-made async sender, and the `schema`
+//! feature for JSON Schema documents describing every payload.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+mod discovery;
+pub use discovery::{config_dir, discover_port, DEFAULT_PORT};
+
+#[cfg(feature = "client")]
+mod client;
+#[cfg(feature = "client")]
+pub use client::OverlayClient;
+
+#[cfg(feature = "schema")]
+mod schema;
+#[cfg(feature = "schema")]
+pub use schema::{schema_for, SCHEMA_TYPES};
+
+// Track info from Spotify
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TrackInfo {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub album_art: Option<String>,
+    pub duration: u64,
+    #[serde(default)]
+    pub profanity_masked: bool, // Explicit words in the lyrics were masked, set by the overlay
+}
+
+// Single lyric line
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LyricLine {
+    pub start_time: i64,
+    pub end_time: Option<i64>,
+    pub text: String,           // Original text
+    #[serde(default)]
+    pub pron_text: Option<String>,  // Phonetic/romanized text
+    #[serde(default)]
+    pub trans_text: Option<String>, // Translation text
+    #[serde(default)]
+    pub translations: Option<BTreeMap<String, String>>, // Translations by language code, e.g. "en", "ko"
+    #[serde(default)]
+    pub words: Option<Vec<LyricWord>>, // Word/syllable timing for karaoke highlighting
+    #[serde(default)]
+    pub singer: Option<String>, // Vocalist/agent id for duets, e.g. "v1", "v2"
+    #[serde(default)]
+    pub background: Option<BackgroundVocals>, // Backing vocals sung alongside the line
+    #[serde(default)]
+    pub ruby: Option<Vec<RubySegment>>, // `text` split into segments, with readings to draw above annotated ones
+    #[serde(default)]
+    pub script: Option<Script>, // Dominant script of `text`, filled in by the overlay
+    #[serde(default)]
+    pub direction: Option<TextDirection>, // Base direction of `text`, filled in by the overlay
+    #[serde(default)]
+    pub pron_direction: Option<TextDirection>, // Base direction of `pron_text`, filled in by the overlay
+    #[serde(default)]
+    pub trans_direction: Option<TextDirection>, // Base direction of `trans_text`, filled in by the overlay
+}
+
+// Background vocals attached to a line
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundVocals {
+    pub text: String,
+    #[serde(default)]
+    pub words: Option<Vec<LyricWord>>,
+}
+
+// Part of a line's text; concatenated texts make up the line text
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RubySegment {
+    pub text: String,
+    #[serde(default)]
+    pub reading: Option<String>, // Furigana or pinyin shown above `text`; None for plain runs
+}
+
+// Timed word or syllable within a line; concatenated texts make up the line text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LyricWord {
+    pub start_time: i64,
+    #[serde(default)]
+    pub end_time: Option<i64>,
+    pub text: String,
+}
+
+// Full lyrics data payload
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LyricsData {
+    pub track: TrackInfo,
+    pub lyrics: Vec<LyricLine>,
+    pub is_synced: bool,
+    #[serde(default)]
+    pub script: Option<Script>, // Dominant script across all lines, filled in by the overlay
+    #[serde(default)]
+    pub direction: Option<TextDirection>, // Base reading direction, filled in by the overlay
+    #[serde(default)]
+    pub translation_languages: Option<Vec<String>>, // Languages kept in lines' `translations`, most preferred first; filled in by the overlay
+}
+
+// Unicode script a line is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Georgian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Tamil,
+    Thai,
+    Lao,
+    Khmer,
+    Hangul,
+    Kana, // Japanese: kana, or kanji in a line or song that also has kana
+    Han,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+// Progress sync data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressData {
+    pub position: u64,
+    pub is_playing: bool,
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub remaining: Option<f64>,
+    #[serde(default)]
+    pub next_track: Option<NextTrackInfo>,
+    #[serde(default)]
+    pub relay_offset: Option<u64>, // ms spent between relay receipt and delivery; add to position
+}
+
+// Next track info for preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NextTrackInfo {
+    pub title: String,
+    pub artist: String,
+    pub album_art: Option<String>,
+}
@@ -0,0 +1,36 @@
+use crate::{
+    BackgroundVocals, LyricLine, LyricWord, LyricsData, NextTrackInfo, ProgressData, RubySegment, Script,
+    TextDirection, TrackInfo,
+};
+
+/// Names accepted by `schema_for`, matching the Rust type names.
+pub const SCHEMA_TYPES: &[&str] = &[
+    "LyricsData",
+    "ProgressData",
+    "TrackInfo",
+    "LyricLine",
+    "LyricWord",
+    "BackgroundVocals",
+    "RubySegment",
+    "NextTrackInfo",
+    "Script",
+    "TextDirection",
+];
+
+/// JSON Schema (draft 7) for the named payload type, or `None` if the name is unknown.
+pub fn schema_for(name: &str) -> Option<serde_json::Value> {
+    let schema = match name {
+        "LyricsData" => schemars::schema_for!(LyricsData),
+        "ProgressData" => schemars::schema_for!(ProgressData),
+        "TrackInfo" => schemars::schema_for!(TrackInfo),
+        "LyricLine" => schemars::schema_for!(LyricLine),
+        "LyricWord" => schemars::schema_for!(LyricWord),
+        "BackgroundVocals" => schemars::schema_for!(BackgroundVocals),
+        "RubySegment" => schemars::schema_for!(RubySegment),
+        "NextTrackInfo" => schemars::schema_for!(NextTrackInfo),
+        "Script" => schemars::schema_for!(Script),
+        "TextDirection" => schemars::schema_for!(TextDirection),
+        _ => return None,
+    };
+    serde_json::to_value(schema).ok()
+}
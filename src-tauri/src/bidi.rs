@@ -0,0 +1,160 @@
+use crate::{LyricsData, TextDirection};
+
+// Directional isolates (UAX #9): LRI / RLI open a run, PDI closes it
+const LRI: char = '\u{2066}';
+const RLI: char = '\u{2067}';
+const FSI: char = '\u{2068}';
+const PDI: char = '\u{2069}';
+
+// Sender text that already carries explicit embeddings, overrides or isolates
+fn has_explicit_marks(text: &str) -> bool {
+    text.chars().any(|c| matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'))
+}
+
+// Strong direction of a character; digits, punctuation, spaces and symbols are neutral here
+pub fn strong_direction(c: char) -> Option<TextDirection> {
+    match c {
+        '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFC}' | '\u{10800}'..='\u{10FFF}'
+            if !c.is_numeric() =>
+        {
+            Some(TextDirection::Rtl)
+        }
+        '\u{200F}' => Some(TextDirection::Rtl),
+        '\u{200E}' => Some(TextDirection::Ltr),
+        _ if c.is_alphabetic() => Some(TextDirection::Ltr),
+        _ => None,
+    }
+}
+
+// Base direction of a paragraph (UAX #9 P2/P3): the direction of the first strong
+// character, skipping text inside isolates
+pub fn base_direction(text: &str) -> Option<TextDirection> {
+    let mut isolates = 0usize;
+    for c in text.chars() {
+        match c {
+            LRI | RLI | FSI => isolates += 1,
+            PDI => isolates = isolates.saturating_sub(1),
+            _ if isolates == 0 => {
+                if let Some(direction) = strong_direction(c) {
+                    return Some(direction);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Wrap each run against the base direction in an isolate, so the neutrals
+// inside it (spaces, punctuation, digits) stay with the run instead of being
+// reordered by the surrounding text
+pub fn isolate_runs(text: &str, base: TextDirection) -> String {
+    if has_explicit_marks(text) {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let directions: Vec<Option<TextDirection>> = chars.iter().map(|&c| strong_direction(c)).collect();
+    let opposite = match base {
+        TextDirection::Ltr => TextDirection::Rtl,
+        TextDirection::Rtl => TextDirection::Ltr,
+    };
+    if !directions.contains(&Some(opposite)) || !directions.contains(&Some(base)) {
+        return text.to_string();
+    }
+
+    let open = match opposite {
+        TextDirection::Ltr => LRI,
+        TextDirection::Rtl => RLI,
+    };
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut i = 0;
+    while i < chars.len() {
+        if directions[i] != Some(opposite) {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        // The run ends at its last opposite-direction character before one in the base direction
+        let mut end = i;
+        for (k, direction) in directions.iter().enumerate().skip(i + 1) {
+            match direction {
+                Some(d) if *d == base => break,
+                Some(_) => end = k,
+                None => {}
+            }
+        }
+
+        out.push(open);
+        out.extend(&chars[i..=end]);
+        out.push(PDI);
+        i = end + 1;
+    }
+    out
+}
+
+pub fn strip_isolates(text: &str) -> String {
+    text.chars().filter(|&c| !matches!(c, LRI | RLI | PDI)).collect()
+}
+
+// Remove the isolates apply_bidi added, for lyrics leaving the overlay (e.g. exported files)
+pub fn strip_lyrics(lyrics_data: &mut LyricsData) {
+    for line in &mut lyrics_data.lyrics {
+        line.text = strip_isolates(&line.text);
+        let translations = line.translations.iter_mut().flat_map(|t| t.values_mut());
+        for text in [&mut line.pron_text, &mut line.trans_text].into_iter().flatten().chain(translations) {
+            *text = strip_isolates(text);
+        }
+    }
+}
+
+// Base direction of a text, and the text with mixed runs isolated
+pub fn directed(text: &str) -> (String, Option<TextDirection>) {
+    let direction = base_direction(text);
+    match direction {
+        Some(direction) => (isolate_runs(text, direction), Some(direction)),
+        None => (text.to_string(), None),
+    }
+}
+
+// Attach base directions to text, pron_text and trans_text, isolate mixed runs,
+// and set the overall direction from what most lines use
+pub fn apply_bidi(lyrics_data: &mut LyricsData) {
+    let (mut ltr_lines, mut rtl_lines) = (0, 0);
+
+    for line in &mut lyrics_data.lyrics {
+        line.direction = base_direction(&line.text);
+        if let Some(direction) = line.direction {
+            // Word-timed and ruby lines are drawn in pieces, which must still add up to the text
+            if line.words.is_none() && line.ruby.is_none() {
+                line.text = isolate_runs(&line.text, direction);
+            }
+            match direction {
+                TextDirection::Ltr => ltr_lines += 1,
+                TextDirection::Rtl => rtl_lines += 1,
+            }
+        }
+
+        for (text, direction) in [
+            (&mut line.pron_text, &mut line.pron_direction),
+            (&mut line.trans_text, &mut line.trans_direction),
+        ] {
+            if let Some(text) = text.as_mut() {
+                (*text, *direction) = directed(text);
+            } else {
+                *direction = None;
+            }
+        }
+        if let Some(translations) = line.translations.as_mut() {
+            for text in translations.values_mut() {
+                *text = directed(text).0;
+            }
+        }
+    }
+
+    lyrics_data.direction = match (ltr_lines, rtl_lines) {
+        (0, 0) => None,
+        (ltr, rtl) if rtl > ltr => Some(TextDirection::Rtl),
+        _ => Some(TextDirection::Ltr),
+    };
+}
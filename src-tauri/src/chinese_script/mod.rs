@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::romanize::kana;
+use crate::{config, LyricsData};
+
+const CHINESE_SCRIPT_FILE: &str = "chinese_script.json";
+
+const SIMPLIFIED_TO_TRADITIONAL: &str = include_str!("s2t.txt");
+const TRADITIONAL_TO_SIMPLIFIED: &str = include_str!("t2s.txt");
+const SIMPLIFIED_TO_TRADITIONAL_PHRASES: &str = include_str!("s2t_phrases.txt");
+const TRADITIONAL_TO_SIMPLIFIED_PHRASES: &str = include_str!("t2s_phrases.txt");
+
+// Which Chinese script lyric text is shown in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChineseScript {
+    #[default]
+    Original, // As the sender wrote it
+    Simplified,
+    Traditional,
+}
+
+pub fn load_chinese_script() -> ChineseScript {
+    config::load_json(CHINESE_SCRIPT_FILE)
+}
+
+pub fn save_chinese_script(script: ChineseScript) -> Result<(), String> {
+    config::save_json(CHINESE_SCRIPT_FILE, &script)
+}
+
+fn parse_pairs(table: &'static str) -> HashMap<char, char> {
+    table
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|line| {
+            let mut chars = line.chars();
+            Some((chars.next()?, chars.next()?))
+        })
+        .collect()
+}
+
+// Whole-word exceptions to a per-character table, one "word replacement" per line
+pub struct PhraseTable {
+    phrases: HashMap<Vec<char>, &'static str>,
+    max_len: usize, // Longest word, in characters
+}
+
+impl PhraseTable {
+    pub fn parse(table: &'static str) -> Self {
+        let phrases: HashMap<Vec<char>, &'static str> = table
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .filter_map(|line| line.split_once(' '))
+            .map(|(word, replacement)| (word.chars().collect(), replacement.trim()))
+            .collect();
+        let max_len = phrases.keys().map(Vec::len).max().unwrap_or(0);
+        Self { phrases, max_len }
+    }
+
+    // Longest word starting at `start`, with its length in characters
+    pub fn longest(&self, chars: &[char], start: usize) -> Option<(usize, &'static str)> {
+        let max_len = self.max_len.min(chars.len() - start);
+        (2..=max_len)
+            .rev()
+            .find_map(|len| self.phrases.get(&chars[start..start + len]).map(|&replacement| (len, replacement)))
+    }
+}
+
+fn phrases(script: ChineseScript) -> Option<&'static PhraseTable> {
+    static TO_TRADITIONAL: OnceLock<PhraseTable> = OnceLock::new();
+    static TO_SIMPLIFIED: OnceLock<PhraseTable> = OnceLock::new();
+    match script {
+        ChineseScript::Original => None,
+        ChineseScript::Simplified => Some(TO_SIMPLIFIED.get_or_init(|| PhraseTable::parse(TRADITIONAL_TO_SIMPLIFIED_PHRASES))),
+        ChineseScript::Traditional => Some(TO_TRADITIONAL.get_or_init(|| PhraseTable::parse(SIMPLIFIED_TO_TRADITIONAL_PHRASES))),
+    }
+}
+
+fn table(script: ChineseScript) -> Option<&'static HashMap<char, char>> {
+    static TO_TRADITIONAL: OnceLock<HashMap<char, char>> = OnceLock::new();
+    static TO_SIMPLIFIED: OnceLock<HashMap<char, char>> = OnceLock::new();
+    match script {
+        ChineseScript::Original => None,
+        ChineseScript::Simplified => Some(TO_SIMPLIFIED.get_or_init(|| parse_pairs(TRADITIONAL_TO_SIMPLIFIED))),
+        ChineseScript::Traditional => Some(TO_TRADITIONAL.get_or_init(|| parse_pairs(SIMPLIFIED_TO_TRADITIONAL))),
+    }
+}
+
+// Convert a string word by word where the phrase table knows the word,
+// character by character otherwise
+pub fn convert(text: &str, script: ChineseScript) -> String {
+    let (Some(table), Some(phrases)) = (table(script), phrases(script)) else {
+        return text.to_string();
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some((len, replacement)) = phrases.longest(&chars, i) {
+            out.push_str(replacement);
+            i += len;
+        } else {
+            out.push(*table.get(&chars[i]).unwrap_or(&chars[i]));
+            i += 1;
+        }
+    }
+    out
+}
+
+// Convert line text, ruby, word text and background vocals; pron/trans rows are left alone.
+// Japanese lyrics keep their kanji as written.
+pub fn convert_lyrics(lyrics_data: &mut LyricsData, script: ChineseScript) {
+    if script == ChineseScript::Original
+        || lyrics_data.lyrics.iter().any(|line| kana::contains_kana(&line.text))
+    {
+        return;
+    }
+    for line in &mut lyrics_data.lyrics {
+        line.text = convert(&line.text, script);
+        for segment in line.ruby.iter_mut().flatten() {
+            segment.text = convert(&segment.text, script);
+        }
+
+        if let Some(background) = line.background.as_mut() {
+            background.text = convert(&background.text, script);
+        }
+        let background_words = line.background.as_mut().and_then(|bg| bg.words.as_mut());
+        for word in line.words.iter_mut().chain(background_words).flatten() {
+            word.text = convert(&word.text, script);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, ChineseScript};
+
+    #[test]
+    fn phrases_pick_the_right_traditional_form() {
+        assert_eq!(convert("头发", ChineseScript::Traditional), "頭髮");
+        assert_eq!(convert("我发现你的长发", ChineseScript::Traditional), "我發現你的長髮");
+        assert_eq!(convert("准备好了", ChineseScript::Traditional), "準備好了");
+        assert_eq!(convert("乾坤", ChineseScript::Simplified), "乾坤");
+        assert_eq!(convert("頭髮乾了", ChineseScript::Simplified), "头发干了");
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+// Config directory (<config>/ivlyrics-overlay), created on demand
+pub fn app_config_dir() -> Result<PathBuf, String> {
+    let app_config_dir = ivlyrics_protocol::config_dir()
+        .ok_or_else(|| "Could not find config directory".to_string())?;
+
+    if !app_config_dir.exists() {
+        std::fs::create_dir_all(&app_config_dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    Ok(app_config_dir)
+}
+
+// Load a JSON config file, falling back to defaults when missing or unreadable
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    ivlyrics_protocol::config_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join(file_name)).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// Save a JSON config file
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let config_path = app_config_dir()?.join(file_name);
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    std::fs::write(&config_path, json)
+        .map_err(|e| format!("Failed to save {}: {}", file_name, e))
+}
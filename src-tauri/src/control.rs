@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, Runtime};
+
+use crate::{network, show_or_create_settings_window, AppLockState, AppState};
+
+// Overlay actions shared by the tray menu and the HTTP command API
+
+pub fn set_locked<R: Runtime>(app: &AppHandle<R>, locked: bool) {
+    let state = app.state::<Arc<Mutex<AppLockState>>>();
+    if let Ok(mut lock_state) = state.lock() {
+        lock_state.is_locked = locked;
+    }
+
+    // Emit event to frontend to update UI
+    let _ = app.emit("lock-state-update", locked);
+}
+
+pub fn toggle_lock<R: Runtime>(app: &AppHandle<R>) {
+    let locked = app
+        .state::<Arc<Mutex<AppLockState>>>()
+        .lock()
+        .map(|s| s.is_locked)
+        .unwrap_or(true);
+    set_locked(app, !locked);
+}
+
+pub fn reset_position<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_position(PhysicalPosition::new(100, 100));
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn set_overlay_visible<R: Runtime>(app: &AppHandle<R>, visible: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = if visible { window.show() } else { window.hide() };
+    }
+}
+
+pub fn open_settings<R: Runtime>(app: &AppHandle<R>) {
+    let _ = show_or_create_settings_window(app);
+}
+
+// Display toggles; only the fields present are changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayToggles {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_original: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_phonetic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_translation: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_track_info: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_album_art: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_next_track: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PresetRequest {
+    id: String,
+}
+
+// `/command/*` routes; every request needs the access token, loopback included
+pub fn command_router<R: Runtime>(access_token: String) -> Router<Arc<AppState<R>>> {
+    Router::new()
+        .route("/lock", post(handle_lock::<R>))
+        .route("/unlock", post(handle_unlock::<R>))
+        .route("/toggle-lock", post(handle_toggle_lock::<R>))
+        .route("/show", post(handle_show::<R>))
+        .route("/hide", post(handle_hide::<R>))
+        .route("/reset-position", post(handle_reset_position::<R>))
+        .route("/settings", post(handle_settings::<R>))
+        .route("/quit", post(handle_quit::<R>))
+        .route("/preset", post(handle_preset::<R>))
+        .route("/display", post(handle_display::<R>))
+        .route_layer(axum::middleware::from_fn_with_state(
+            Arc::new(access_token),
+            require_token,
+        ))
+}
+
+async fn require_token(
+    State(access_token): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = network::request_token(request.headers(), request.uri());
+    if !network::token_matches(&access_token, token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, "Invalid access token").into_response();
+    }
+    next.run(request).await
+}
+
+// Window calls go through the main thread, like the tray handlers
+fn on_main_thread<R: Runtime>(state: &AppState<R>, f: fn(&AppHandle<R>)) -> StatusCode {
+    let handle = state.app_handle.clone();
+    match state.app_handle.run_on_main_thread(move || f(&handle)) {
+        Ok(()) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn handle_lock<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, |app| set_locked(app, true))
+}
+
+async fn handle_unlock<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, |app| set_locked(app, false))
+}
+
+async fn handle_toggle_lock<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, toggle_lock)
+}
+
+async fn handle_show<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, |app| set_overlay_visible(app, true))
+}
+
+async fn handle_hide<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, |app| set_overlay_visible(app, false))
+}
+
+async fn handle_reset_position<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, reset_position)
+}
+
+async fn handle_settings<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, open_settings)
+}
+
+async fn handle_quit<R: Runtime>(State(state): State<Arc<AppState<R>>>) -> StatusCode {
+    on_main_thread(&state, |app| app.exit(0))
+}
+
+async fn handle_preset<R: Runtime>(
+    State(state): State<Arc<AppState<R>>>,
+    Json(preset): Json<PresetRequest>,
+) -> StatusCode {
+    // Presets live in the frontend; the main window applies it
+    match state.app_handle.emit("apply-preset", preset.id) {
+        Ok(()) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn handle_display<R: Runtime>(
+    State(state): State<Arc<AppState<R>>>,
+    Json(toggles): Json<DisplayToggles>,
+) -> StatusCode {
+    match state.app_handle.emit("display-toggles", toggles) {
+        Ok(()) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
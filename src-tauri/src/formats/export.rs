@@ -0,0 +1,289 @@
+use std::fmt::Write;
+
+use crate::{bidi, LyricLine, LyricWord, LyricsData};
+
+// Formats the current lyrics can be written out as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Lrc,
+    Srt,
+    Ass,
+}
+
+impl ExportFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "lrc" => Some(Self::Lrc),
+            "srt" => Some(Self::Srt),
+            "ass" => Some(Self::Ass),
+            _ => None,
+        }
+    }
+}
+
+// Lines without an end time last until the next line, or this long at the end
+const LAST_LINE_DURATION: i64 = 5000;
+
+pub fn export_lyrics(lyrics_data: &LyricsData, format: ExportFormat) -> Result<String, String> {
+    if format != ExportFormat::Lrc && !lyrics_data.is_synced {
+        return Err("Only synced lyrics can be exported as subtitles".to_string());
+    }
+    // Isolate marks only help draw mixed-direction lines here; files get the plain text
+    let mut lyrics_data = lyrics_data.clone();
+    bidi::strip_lyrics(&mut lyrics_data);
+    Ok(match format {
+        ExportFormat::Lrc => to_lrc(&lyrics_data),
+        ExportFormat::Srt => to_srt(&lyrics_data),
+        ExportFormat::Ass => to_ass(&lyrics_data),
+    })
+}
+
+// LRC with pron/trans lines sharing the original's timestamp (the layout the importer reads),
+// and Enhanced LRC word tags when word timing exists
+pub fn to_lrc(lyrics_data: &LyricsData) -> String {
+    let mut out = String::new();
+    let track = &lyrics_data.track;
+    for (tag, value) in [("ti", &track.title), ("ar", &track.artist), ("al", &track.album)] {
+        if !value.is_empty() {
+            let _ = writeln!(out, "[{}:{}]", tag, value);
+        }
+    }
+    if track.duration > 0 {
+        let _ = writeln!(out, "[length:{}]", lrc_time(track.duration as i64));
+    }
+
+    for line in &lyrics_data.lyrics {
+        if !lyrics_data.is_synced {
+            let _ = writeln!(out, "{}", line.text);
+            continue;
+        }
+
+        let stamp = format!("[{}]", lrc_time(line.start_time));
+        let text = match &line.words {
+            Some(words) if !words.is_empty() => enhanced_lrc_text(words),
+            _ => line.text.clone(),
+        };
+        let _ = writeln!(out, "{}{}", stamp, text);
+
+        let pron = line.pron_text.as_deref().filter(|t| !t.is_empty());
+        let trans = line.trans_text.as_deref().filter(|t| !t.is_empty());
+        if let (Some(pron), Some(_)) = (pron, trans) {
+            let _ = writeln!(out, "{}{}", stamp, pron);
+        }
+        if let Some(trans) = trans {
+            let _ = writeln!(out, "{}{}", stamp, trans);
+        }
+    }
+    out
+}
+
+// SRT cues with pron/trans text on extra lines
+pub fn to_srt(lyrics_data: &LyricsData) -> String {
+    let mut out = String::new();
+    let mut index = 1;
+    for (i, line) in lyrics_data.lyrics.iter().enumerate() {
+        if line.text.trim().is_empty() {
+            continue;
+        }
+        let end = line_end(&lyrics_data.lyrics, i);
+        let _ = writeln!(out, "{}", index);
+        let _ = writeln!(out, "{} --> {}", srt_time(line.start_time), srt_time(end));
+        let _ = writeln!(out, "{}", line.text);
+        for extra in [&line.pron_text, &line.trans_text].into_iter().flatten() {
+            if !extra.is_empty() {
+                let _ = writeln!(out, "{}", extra);
+            }
+        }
+        let _ = writeln!(out);
+        index += 1;
+    }
+    out
+}
+
+// ASS with {\k} karaoke tags for word-timed lines, translations on their own style
+pub fn to_ass(lyrics_data: &LyricsData) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "[Script Info]");
+    let _ = writeln!(out, "Title: {}", lyrics_data.track.title);
+    let _ = writeln!(out, "ScriptType: v4.00+");
+    let _ = writeln!(out, "PlayResX: 1920");
+    let _ = writeln!(out, "PlayResY: 1080");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "[V4+ Styles]");
+    let _ = writeln!(out, "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding");
+    let _ = writeln!(out, "Style: Default,Arial,64,&H0054B91D,&H00FFFFFF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,3,0,2,60,60,120,1");
+    let _ = writeln!(out, "Style: Translation,Arial,44,&H00AAAAAA,&H00AAAAAA,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,2,0,2,60,60,50,1");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "[Events]");
+    let _ = writeln!(out, "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text");
+
+    for (i, line) in lyrics_data.lyrics.iter().enumerate() {
+        if line.text.trim().is_empty() {
+            continue;
+        }
+        let start = ass_time(line.start_time);
+        let end = ass_time(line_end(&lyrics_data.lyrics, i));
+        let text = match &line.words {
+            Some(words) if !words.is_empty() => karaoke_text(line, words),
+            _ => ass_escape(&line.text),
+        };
+        let _ = writeln!(out, "Dialogue: 0,{},{},Default,{},0,0,0,,{}", start, end, line.singer.as_deref().unwrap_or(""), text);
+
+        if let Some(trans) = line.trans_text.as_deref().filter(|t| !t.is_empty()) {
+            let _ = writeln!(out, "Dialogue: 0,{},{},Translation,,0,0,0,,{}", start, end, ass_escape(trans));
+        }
+    }
+    out
+}
+
+fn line_end(lyrics: &[LyricLine], index: usize) -> i64 {
+    let line = &lyrics[index];
+    line.end_time
+        .or_else(|| lyrics.get(index + 1).map(|next| next.start_time))
+        .filter(|end| *end > line.start_time)
+        .unwrap_or(line.start_time + LAST_LINE_DURATION)
+}
+
+// A word's end gets its own tag when a gap or the end of the line follows it
+fn enhanced_lrc_text(words: &[LyricWord]) -> String {
+    let mut text = String::new();
+    for (i, word) in words.iter().enumerate() {
+        let _ = write!(text, "<{}>{}", lrc_time(word.start_time), word.text);
+        let next_start = words.get(i + 1).map(|next| next.start_time);
+        if let Some(end) = word.end_time.filter(|end| Some(*end) != next_start) {
+            let _ = write!(text, "<{}>", lrc_time(end));
+        }
+    }
+    text
+}
+
+// {\k} durations are centiseconds; gaps before a word become empty {\k} runs
+fn karaoke_text(line: &LyricLine, words: &[LyricWord]) -> String {
+    let mut text = String::new();
+    let mut cursor = line.start_time;
+    for (i, word) in words.iter().enumerate() {
+        if word.start_time > cursor {
+            let _ = write!(text, "{{\\k{}}}", (word.start_time - cursor) / 10);
+        }
+        let end = word
+            .end_time
+            .or_else(|| words.get(i + 1).map(|next| next.start_time))
+            .unwrap_or(word.start_time)
+            .max(word.start_time);
+        let _ = write!(text, "{{\\k{}}}{}", (end - word.start_time) / 10, ass_escape(&word.text));
+        cursor = end.max(cursor);
+    }
+    text
+}
+
+fn ass_escape(text: &str) -> String {
+    text.replace('{', "(").replace('}', ")").replace('\n', "\\N")
+}
+
+// mm:ss.xx
+fn lrc_time(ms: i64) -> String {
+    let ms = ms.max(0);
+    format!("{:02}:{:02}.{:02}", ms / 60_000, ms / 1000 % 60, ms % 1000 / 10)
+}
+
+// hh:mm:ss,mmm
+fn srt_time(ms: i64) -> String {
+    let ms = ms.max(0);
+    format!("{:02}:{:02}:{:02},{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+// h:mm:ss.cc
+fn ass_time(ms: i64) -> String {
+    let ms = ms.max(0);
+    format!("{}:{:02}:{:02}.{:02}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000 / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{empty_track, lrc};
+
+    fn lyrics() -> LyricsData {
+        let mut track = empty_track();
+        track.title = "Song".to_string();
+        track.artist = "Artist".to_string();
+        track.duration = 200_000;
+        LyricsData {
+            track,
+            lyrics: vec![
+                LyricLine {
+                    start_time: 1000,
+                    text: "Hello world".to_string(),
+                    words: Some(vec![
+                        LyricWord {
+                            start_time: 1000,
+                            end_time: Some(1500),
+                            text: "Hello ".to_string(),
+                        },
+                        LyricWord {
+                            start_time: 2000,
+                            end_time: Some(2500),
+                            text: "world".to_string(),
+                        },
+                    ]),
+                    ..Default::default()
+                },
+                LyricLine {
+                    start_time: 5000,
+                    text: "こんにちは".to_string(),
+                    pron_text: Some("konnichiwa".to_string()),
+                    trans_text: Some("Hi {there}".to_string()),
+                    ..Default::default()
+                },
+            ],
+            is_synced: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lrc_round_trip() {
+        let original = lyrics();
+        let parsed = lrc::parse_lrc(&to_lrc(&original), empty_track());
+        assert_eq!(parsed.track.title, "Song");
+        assert_eq!(parsed.track.duration, 200_000);
+        for (parsed, original) in parsed.lyrics.iter().zip(&original.lyrics) {
+            assert_eq!(parsed.start_time, original.start_time);
+            assert_eq!(parsed.text, original.text);
+            assert_eq!(parsed.pron_text, original.pron_text);
+            assert_eq!(parsed.trans_text, original.trans_text);
+            let timing = |words: &Option<Vec<LyricWord>>| {
+                words
+                    .iter()
+                    .flatten()
+                    .map(|w| (w.start_time, w.end_time, w.text.clone()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(timing(&parsed.words), timing(&original.words));
+        }
+    }
+
+    #[test]
+    fn srt_cues() {
+        let srt = to_srt(&lyrics());
+        assert!(srt.starts_with("1\n00:00:01,000 --> 00:00:05,000\nHello world\n\n2\n"));
+        // The last line has no end: it gets LAST_LINE_DURATION
+        assert!(srt.contains("00:00:05,000 --> 00:00:10,000\nこんにちは\nkonnichiwa\nHi {there}\n"));
+    }
+
+    #[test]
+    fn ass_karaoke() {
+        let ass = to_ass(&lyrics());
+        assert!(ass.contains("Dialogue: 0,0:00:01.00,0:00:05.00,Default,,0,0,0,,{\\k50}Hello {\\k50}{\\k50}world"));
+        assert!(ass.contains("Translation,,0,0,0,,Hi (there)"));
+    }
+
+    #[test]
+    fn unsynced_lyrics_only_as_lrc() {
+        let mut lyrics_data = lyrics();
+        lyrics_data.is_synced = false;
+        assert!(export_lyrics(&lyrics_data, ExportFormat::Srt).is_err());
+        let lrc = export_lyrics(&lyrics_data, ExportFormat::Lrc).unwrap();
+        assert!(lrc.ends_with("Hello world\nこんにちは\n"));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::{LyricLine, LyricWord, LyricsData, TrackInfo};
+
+// One timestamped entry before lines sharing a timestamp are merged
+struct LrcEntry {
+    time: i64,
+    order: usize,
+    text: String,
+    words: Option<Vec<LyricWord>>,
+}
+
+// Parse standard and Enhanced LRC. Entries sharing a timestamp are read as
+// translation pairs: the first is the original, a second becomes trans_text,
+// and with three the middle one is taken as pron_text.
+// Enhanced LRC `<mm:ss.xx>` word tags become the line's `words`.
+pub fn parse_lrc(content: &str, mut track: TrackInfo) -> LyricsData {
+    let mut offset: i64 = 0;
+    let mut entries: Vec<LrcEntry> = Vec::new();
+    let mut plain_lines: Vec<String> = Vec::new();
+
+    for raw_line in content.lines() {
+        let mut rest = raw_line.trim();
+        let mut times = Vec::new();
+
+        // Leading tags: any number of timestamps, or a single metadata tag
+        while let Some(tag_end) = rest.strip_prefix('[').and_then(|r| r.find(']')) {
+            let tag = &rest[1..tag_end + 1];
+            if let Some(time) = parse_timestamp(tag) {
+                times.push(time);
+                rest = rest[tag_end + 2..].trim_start();
+                continue;
+            }
+
+            if times.is_empty() {
+                if let Some((key, value)) = tag.split_once(':') {
+                    let value = value.trim();
+                    match key.trim().to_ascii_lowercase().as_str() {
+                        "ti" if track.title.is_empty() => track.title = value.to_string(),
+                        "ar" if track.artist.is_empty() => track.artist = value.to_string(),
+                        "al" if track.album.is_empty() => track.album = value.to_string(),
+                        "length" if track.duration == 0 => {
+                            track.duration = parse_timestamp(value).unwrap_or(0).max(0) as u64;
+                        }
+                        "offset" => offset = value.trim_start_matches('+').parse().unwrap_or(0),
+                        _ => {}
+                    }
+                    rest = "";
+                }
+            }
+            break;
+        }
+
+        if times.is_empty() {
+            if !rest.is_empty() {
+                plain_lines.push(rest.to_string());
+            }
+            continue;
+        }
+
+        let (text, words) = parse_word_tags(rest);
+        for time in times {
+            entries.push(LrcEntry {
+                time,
+                order: entries.len(),
+                text: text.clone(),
+                words: words.clone(),
+            });
+        }
+    }
+
+    // No timestamps at all: plain unsynced text
+    if entries.is_empty() {
+        return LyricsData {
+            track,
+            lyrics: plain_lines
+                .into_iter()
+                .map(|text| LyricLine {
+                    text,
+                    ..Default::default()
+                })
+                .collect(),
+            is_synced: false,
+            ..Default::default()
+        };
+    }
+
+    entries.sort_by_key(|e| (e.time, e.order));
+
+    let mut lyrics: Vec<LyricLine> = Vec::new();
+    let mut entries = entries.into_iter().peekable();
+    while let Some(first) = entries.next() {
+        let time = first.time;
+        let mut texts = vec![first];
+        while let Some(next) = entries.next_if(|e| e.time == time) {
+            texts.push(next);
+        }
+
+        // Blank entries only matter when nothing else shares the timestamp
+        if texts.iter().any(|e| !e.text.is_empty()) {
+            texts.retain(|e| !e.text.is_empty());
+        } else {
+            texts.truncate(1);
+        }
+
+        let (pron_text, trans_text) = match texts.len() {
+            1 => (None, None),
+            2 => (None, Some(texts.remove(1).text)),
+            _ => (Some(texts.remove(1).text), Some(texts.remove(1).text)),
+        };
+
+        let original = texts.remove(0);
+        let words = original.words.map(|words| {
+            words
+                .into_iter()
+                .map(|word| LyricWord {
+                    start_time: (word.start_time - offset).max(0),
+                    end_time: word.end_time.map(|end| (end - offset).max(0)),
+                    text: word.text,
+                })
+                .collect()
+        });
+
+        lyrics.push(LyricLine {
+            start_time: (time - offset).max(0),
+            text: original.text,
+            pron_text,
+            trans_text,
+            words,
+            ..Default::default()
+        });
+    }
+
+    // A word without a closing tag lasts until the next line starts
+    for index in 1..lyrics.len() {
+        let next_start = lyrics[index].start_time;
+        if let Some(last) = lyrics[index - 1].words.as_mut().and_then(|w| w.last_mut()) {
+            if last.end_time.is_none() {
+                last.end_time = Some(next_start);
+            }
+        }
+    }
+
+    LyricsData {
+        track,
+        lyrics,
+        is_synced: true,
+        ..Default::default()
+    }
+}
+
+// Split Enhanced LRC text like "<00:12.00>Hello <00:12.50>world<00:13.00>"
+// into plain text and timed words; text without word tags has no words
+fn parse_word_tags(text: &str) -> (String, Option<Vec<LyricWord>>) {
+    let mut words: Vec<LyricWord> = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    let mut current: Option<(i64, String)> = None;
+
+    loop {
+        let tag = rest
+            .find('<')
+            .and_then(|open| rest[open..].find('>').map(|close| (open, open + close)))
+            .and_then(|(open, close)| parse_timestamp(&rest[open + 1..close]).map(|t| (open, close, t)));
+
+        let Some((open, close, time)) = tag else {
+            if let Some((_, segment)) = current.as_mut() {
+                segment.push_str(rest);
+            }
+            plain.push_str(rest);
+            break;
+        };
+
+        let before = &rest[..open];
+        plain.push_str(before);
+        if let Some((start, mut segment)) = current.take() {
+            segment.push_str(before);
+            if !segment.trim().is_empty() {
+                words.push(LyricWord {
+                    start_time: start,
+                    end_time: Some(time),
+                    text: segment,
+                });
+            }
+        }
+        current = Some((time, String::new()));
+        rest = &rest[close + 1..];
+    }
+
+    if let Some((start, segment)) = current {
+        if !segment.trim().is_empty() {
+            words.push(LyricWord {
+                start_time: start,
+                end_time: None,
+                text: segment,
+            });
+        }
+    }
+
+    let plain = plain.trim().to_string();
+    if words.is_empty() {
+        return (plain, None);
+    }
+
+    // Trim the edges so the words still add up to the line text
+    if let Some(first) = words.first_mut() {
+        first.text = first.text.trim_start().to_string();
+    }
+    if let Some(last) = words.last_mut() {
+        last.text = last.text.trim_end().to_string();
+    }
+    (plain, Some(words))
+}
+
+// "mm:ss", "mm:ss.xx", "mm:ss.xxx", "mm:ss:xx" or "hh:mm:ss.xx" -> milliseconds
+pub fn parse_timestamp(tag: &str) -> Option<i64> {
+    let tag = tag.trim();
+    let (clock, fraction) = match tag.rfind('.') {
+        Some(dot) => (&tag[..dot], Some(&tag[dot + 1..])),
+        // mm:ss:xx puts a colon before the centiseconds
+        None if tag.matches(':').count() == 2 => {
+            let last = tag.rfind(':')?;
+            (&tag[..last], Some(&tag[last + 1..]))
+        }
+        None => (tag, None),
+    };
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return None;
+    }
+    if !parts.iter().all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+
+    let mut seconds: i64 = 0;
+    for part in &parts {
+        seconds = seconds * 60 + part.parse::<i64>().ok()?;
+    }
+
+    let millis = match fraction {
+        Some(f) if !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()) => {
+            let digits: String = f.chars().chain("000".chars()).take(3).collect();
+            digits.parse::<i64>().ok()?
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+
+    Some(seconds * 1000 + millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::empty_track;
+
+    #[test]
+    fn timestamp_variants() {
+        assert_eq!(parse_timestamp("01:02"), Some(62_000));
+        assert_eq!(parse_timestamp("01:02.5"), Some(62_500));
+        assert_eq!(parse_timestamp("01:02.34"), Some(62_340));
+        assert_eq!(parse_timestamp("01:02.345"), Some(62_345));
+        assert_eq!(parse_timestamp("01:02:34"), Some(62_340));
+        assert_eq!(parse_timestamp("1:01:02.34"), Some(3_662_340));
+        assert_eq!(parse_timestamp("-00:01.00"), None);
+        assert_eq!(parse_timestamp("ti:Song"), None);
+    }
+
+    #[test]
+    fn metadata_and_offset() {
+        let lrc = "[ti:Song]\n[ar:Artist]\n[length:03:20]\n[offset:+500]\n[00:01.00]Hello\n[00:00.20]Intro\n";
+        let lyrics_data = parse_lrc(lrc, empty_track());
+        assert_eq!(lyrics_data.track.title, "Song");
+        assert_eq!(lyrics_data.track.artist, "Artist");
+        assert_eq!(lyrics_data.track.duration, 200_000);
+        // A positive offset shows lines earlier; times never go below zero
+        let starts: Vec<i64> = lyrics_data.lyrics.iter().map(|line| line.start_time).collect();
+        assert_eq!(starts, [0, 500]);
+
+        let lyrics_data = parse_lrc("[offset:-250]\n[00:01.00]Hello\n", empty_track());
+        assert_eq!(lyrics_data.lyrics[0].start_time, 1250);
+    }
+
+    #[test]
+    fn repeated_timestamps() {
+        let lrc = "[00:01.00][00:10.50]Chorus\n[00:05.00]Verse\n";
+        let lyrics_data = parse_lrc(lrc, empty_track());
+        let lines: Vec<(i64, &str)> = lyrics_data.lyrics.iter().map(|l| (l.start_time, l.text.as_str())).collect();
+        assert_eq!(lines, [(1000, "Chorus"), (5000, "Verse"), (10_500, "Chorus")]);
+    }
+
+    #[test]
+    fn translation_pairs() {
+        let lrc = "[00:05.00]こんにちは\n[00:05.00]konnichiwa\n[00:05.00]Hello\n[00:07.00]Orig\n[00:07.00]Trans\n[00:08.00]\n";
+        let lyrics = parse_lrc(lrc, empty_track()).lyrics;
+        assert_eq!(lyrics[0].text, "こんにちは");
+        assert_eq!(lyrics[0].pron_text.as_deref(), Some("konnichiwa"));
+        assert_eq!(lyrics[0].trans_text.as_deref(), Some("Hello"));
+        assert_eq!(lyrics[1].pron_text, None);
+        assert_eq!(lyrics[1].trans_text.as_deref(), Some("Trans"));
+        // A blank timestamp on its own is kept as a spacer
+        assert_eq!((lyrics[2].start_time, lyrics[2].text.as_str()), (8000, ""));
+    }
+
+    #[test]
+    fn unsynced_text() {
+        let lyrics_data = parse_lrc("[ti:Song]\nFirst\n\nSecond\n", empty_track());
+        assert!(!lyrics_data.is_synced);
+        let texts: Vec<&str> = lyrics_data.lyrics.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["First", "Second"]);
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use crate::{LyricsData, TrackInfo};
+
+pub mod export;
+pub mod lrc;
+pub mod subtitle;
+pub mod ttml;
+
+// Lyrics file formats the importer understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LyricsFormat {
+    Lrc,
+    Ttml,
+    Srt,
+    Vtt,
+}
+
+impl LyricsFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "lrc" => Some(Self::Lrc),
+            "ttml" => Some(Self::Ttml),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+}
+
+// Parse lyrics text; `track` fills whatever metadata the file doesn't carry
+pub fn parse_lyrics(content: &str, format: LyricsFormat, track: TrackInfo) -> Result<LyricsData, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    match format {
+        LyricsFormat::Lrc => Ok(lrc::parse_lrc(content, track)),
+        LyricsFormat::Ttml => ttml::parse_ttml(content, track),
+        LyricsFormat::Srt => subtitle::parse_srt(content, track),
+        LyricsFormat::Vtt => subtitle::parse_vtt(content, track),
+    }
+}
+
+// Read and parse a lyrics file, picking the format from its extension
+pub fn parse_lyrics_file(path: &Path, track: TrackInfo) -> Result<LyricsData, String> {
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(LyricsFormat::from_extension)
+        .ok_or_else(|| format!("Unsupported lyrics file: {}", path.display()))?;
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut lyrics_data = parse_lyrics(&content, format, track)?;
+    if lyrics_data.track.title.is_empty() {
+        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+            lyrics_data.track.title = stem.to_string();
+        }
+    }
+    Ok(lyrics_data)
+}
+
+// Track info with nothing known yet
+pub fn empty_track() -> TrackInfo {
+    TrackInfo {
+        title: String::new(),
+        artist: String::new(),
+        album: String::new(),
+        album_art: None,
+        duration: 0,
+        profanity_masked: false,
+    }
+}
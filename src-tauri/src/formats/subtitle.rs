@@ -0,0 +1,181 @@
+use crate::{LyricLine, LyricsData, TrackInfo};
+
+// Parse SubRip (.srt) cues into synced lines
+pub fn parse_srt(content: &str, track: TrackInfo) -> Result<LyricsData, String> {
+    parse_cues(content, track, false)
+}
+
+// Parse WebVTT (.vtt) cues into synced lines; <v> voice tags become the singer
+pub fn parse_vtt(content: &str, track: TrackInfo) -> Result<LyricsData, String> {
+    if !content.trim_start().starts_with("WEBVTT") {
+        return Err("Invalid WebVTT: missing WEBVTT header".to_string());
+    }
+    parse_cues(content, track, true)
+}
+
+// Both formats are blank-line separated blocks with a "start --> end" timing line
+fn parse_cues(content: &str, track: TrackInfo, is_vtt: bool) -> Result<LyricsData, String> {
+    let content = content.replace("\r\n", "\n");
+    let mut lyrics: Vec<LyricLine> = Vec::new();
+
+    for block in content.split("\n\n") {
+        let mut lines = block.lines().map(str::trim).filter(|l| !l.is_empty());
+
+        // Cue identifier (SRT index or VTT id) may precede the timing line
+        let Some(timing) = lines.by_ref().find(|l| l.contains("-->")) else {
+            continue;
+        };
+        let Some((start, end)) = parse_timing(timing) else {
+            continue;
+        };
+
+        let mut singer = None;
+        let text_lines: Vec<String> = lines
+            .map(|line| {
+                if is_vtt && singer.is_none() {
+                    singer = voice_name(line);
+                }
+                strip_tags(line)
+            })
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        lyrics.push(LyricLine {
+            start_time: start,
+            end_time: Some(end),
+            text: text_lines.join(" "),
+            singer,
+            ..Default::default()
+        });
+    }
+
+    if lyrics.is_empty() {
+        return Err("No subtitle cues found".to_string());
+    }
+
+    lyrics.sort_by_key(|line| line.start_time);
+
+    Ok(LyricsData {
+        track,
+        lyrics,
+        is_synced: true,
+        ..Default::default()
+    })
+}
+
+// "00:00:01,000 --> 00:00:04,000 align:start" -> (start, end)
+fn parse_timing(line: &str) -> Option<(i64, i64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_cue_time(start)?, parse_cue_time(end)?))
+}
+
+// "hh:mm:ss,mmm", "hh:mm:ss.mmm" or "mm:ss.mmm" -> milliseconds
+fn parse_cue_time(value: &str) -> Option<i64> {
+    let value = value.trim().replace(',', ".");
+    let (clock, fraction) = value.split_once('.').unwrap_or((&value, "0"));
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return None;
+    }
+    let mut seconds: i64 = 0;
+    for part in parts {
+        seconds = seconds * 60 + part.parse::<i64>().ok()?;
+    }
+
+    let digits: String = fraction.chars().chain("000".chars()).take(3).collect();
+    Some(seconds * 1000 + digits.parse::<i64>().ok()?)
+}
+
+// "<v Alice>Hello" or "<v.loud Alice>Hello" -> "Alice"
+fn voice_name(line: &str) -> Option<String> {
+    let start = line.find("<v")?;
+    let tag = &line[start + 2..start + line[start..].find('>')?];
+    let name = tag.split_once(char::is_whitespace)?.1.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// Drop <b>, <i>, <c.class>, <v>, <00:01.000> and {\an8}-style tags; decode basic entities.
+// Any other "<" is text, as in "I <3 you".
+fn strip_tags(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut text = String::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let tag_end = match chars[i] {
+            '<' => chars[i..].iter().position(|&c| c == '>').filter(|&len| is_markup(&chars[i + 1..i + len])),
+            '{' if chars.get(i + 1) == Some(&'\\') => chars[i..].iter().position(|&c| c == '}'),
+            _ => None,
+        };
+        match tag_end {
+            Some(len) => i += len + 1,
+            None => {
+                text.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Inside of a recognized tag: formatting, voice, language and ruby tags, <font>, or a timestamp
+fn is_markup(tag: &[char]) -> bool {
+    let tag: String = tag.iter().collect();
+    let name = tag.strip_prefix('/').unwrap_or(&tag);
+    let name = name.split(['.', ' ', '\t']).next().unwrap_or_default();
+    matches!(name, "i" | "b" | "u" | "c" | "v" | "lang" | "ruby" | "rt" | "font") || parse_cue_time(&tag).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::empty_track;
+
+    fn texts(lyrics_data: &LyricsData) -> Vec<&str> {
+        lyrics_data.lyrics.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn srt_cues() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:04,500\r\n<i>Hello</i>\r\nthere &amp; you\r\n\r\n\
+                   2\r\n00:00:05,000 --> 00:00:06,000\r\n{\\an8}<font color=\"red\">Top</font>\r\n";
+        let lyrics_data = parse_srt(srt, empty_track()).unwrap();
+        assert_eq!(texts(&lyrics_data), ["Hello there & you", "Top"]);
+        assert_eq!(lyrics_data.lyrics[0].start_time, 1000);
+        assert_eq!(lyrics_data.lyrics[0].end_time, Some(4500));
+    }
+
+    #[test]
+    fn bare_angle_brackets_are_text() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,000\nI <3 you\n\n2\n00:00:03,000 --> 00:00:04,000\na < b > c\n";
+        let lyrics_data = parse_srt(srt, empty_track()).unwrap();
+        assert_eq!(texts(&lyrics_data), ["I <3 you", "a < b > c"]);
+    }
+
+    #[test]
+    fn vtt_cues() {
+        let vtt = "WEBVTT\nKind: captions\n\nSTYLE\n::cue { color: yellow }\n\nNOTE a comment\nover two lines\n\n\
+                   id1\n00:01.000 --> 00:02.000 align:start\n<v.loud Alice>Hi <b>there</b>\n<c.x>more</c> <00:01.500>words\n";
+        let lyrics_data = parse_vtt(vtt, empty_track()).unwrap();
+        assert_eq!(texts(&lyrics_data), ["Hi there more words"]);
+        assert_eq!(lyrics_data.lyrics[0].singer.as_deref(), Some("Alice"));
+        assert_eq!(lyrics_data.lyrics[0].start_time, 1000);
+        assert_eq!(lyrics_data.lyrics[0].end_time, Some(2000));
+    }
+
+    #[test]
+    fn vtt_requires_header() {
+        assert!(parse_vtt("00:01.000 --> 00:02.000\nHi\n", empty_track()).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+use crate::{BackgroundVocals, LyricLine, LyricWord, LyricsData, TrackInfo};
+
+// Text and word timing collected from a <p> or an x-bg <span>
+#[derive(Default)]
+struct Segments {
+    text: String,
+    words: Vec<LyricWord>,
+    background: Option<Box<Segments>>,
+    pron_text: Option<String>,
+    trans_text: Option<String>,
+}
+
+// Parse Apple-style TTML: <p begin end> lines, <span> word timing,
+// ttm:agent singers, x-bg background vocals, and x-translation / x-roman
+// spans or head <translations>/<transliterations> keyed by itunes:key.
+pub fn parse_ttml(content: &str, mut track: TrackInfo) -> Result<LyricsData, String> {
+    let doc = Document::parse(content).map_err(|e| format!("Invalid TTML: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "tt" {
+        return Err("Invalid TTML: missing <tt> root".to_string());
+    }
+
+    let translations = head_texts(root, "translation");
+    let transliterations = head_texts(root, "transliteration");
+
+    if track.title.is_empty() {
+        if let Some(title) = root.descendants().find(|n| n.tag_name().name() == "title") {
+            track.title = node_text(title).trim().to_string();
+        }
+    }
+    if track.duration == 0 {
+        if let Some(body) = root.descendants().find(|n| n.tag_name().name() == "body") {
+            track.duration = attr(body, "dur").and_then(parse_time).unwrap_or(0).max(0) as u64;
+        }
+    }
+
+    let mut lyrics: Vec<LyricLine> = Vec::new();
+    let mut is_synced = attr(root, "timing") != Some("None");
+
+    for p in root.descendants().filter(|n| n.tag_name().name() == "p") {
+        let begin = attr(p, "begin").and_then(parse_time);
+        if begin.is_none() {
+            is_synced = false;
+        }
+
+        let mut segments = Segments::default();
+        collect_segments(p, &mut segments);
+        let key = attr(p, "key");
+
+        let words = finish_words(segments.words);
+        let background = segments.background.map(|bg| BackgroundVocals {
+            text: collapse_whitespace(&bg.text),
+            words: finish_words(bg.words),
+        });
+
+        lyrics.push(LyricLine {
+            start_time: begin.unwrap_or(0),
+            end_time: attr(p, "end").and_then(parse_time),
+            text: collapse_whitespace(&segments.text),
+            pron_text: segments
+                .pron_text
+                .or_else(|| key.and_then(|k| transliterations.get(k).cloned())),
+            trans_text: segments
+                .trans_text
+                .or_else(|| key.and_then(|k| translations.get(k).cloned())),
+            words,
+            singer: attr(p, "agent").map(|a| a.to_string()),
+            background,
+            ..Default::default()
+        });
+    }
+
+    if lyrics.is_empty() {
+        return Err("TTML contains no lyric lines".to_string());
+    }
+
+    Ok(LyricsData {
+        track,
+        lyrics,
+        is_synced,
+        ..Default::default()
+    })
+}
+
+fn collect_segments(node: Node, segments: &mut Segments) {
+    for child in node.children() {
+        if child.is_text() {
+            let text = child.text().unwrap_or("");
+            segments.text.push_str(text);
+            // Spacing between word spans belongs to the preceding word
+            if let Some(last) = segments.words.last_mut() {
+                last.text.push_str(text);
+            }
+            continue;
+        }
+        if !child.is_element() {
+            continue;
+        }
+
+        match (child.tag_name().name(), attr(child, "role")) {
+            ("br", _) => segments.text.push(' '),
+            (_, Some("x-bg")) => {
+                let background = segments.background.get_or_insert_with(Default::default);
+                if !background.text.is_empty() {
+                    background.text.push(' ');
+                }
+                collect_segments(child, background);
+            }
+            (_, Some("x-translation")) => {
+                segments.trans_text = Some(collapse_whitespace(&node_text(child)));
+            }
+            (_, Some("x-roman")) | (_, Some("x-pronunciation")) => {
+                segments.pron_text = Some(collapse_whitespace(&node_text(child)));
+            }
+            ("span", _) => match attr(child, "begin").and_then(parse_time) {
+                Some(start_time) => {
+                    let text = node_text(child);
+                    segments.text.push_str(&text);
+                    segments.words.push(LyricWord {
+                        start_time,
+                        end_time: attr(child, "end").and_then(parse_time),
+                        text,
+                    });
+                }
+                // Untimed span: a styling wrapper around more content
+                None => collect_segments(child, segments),
+            },
+            _ => {}
+        }
+    }
+}
+
+// Normalize word spacing so the words add up to the collapsed line text
+fn finish_words(words: Vec<LyricWord>) -> Option<Vec<LyricWord>> {
+    let mut words: Vec<LyricWord> = words
+        .into_iter()
+        .filter(|w| !w.text.trim().is_empty())
+        .map(|mut w| {
+            let ends_with_space = w.text.ends_with(char::is_whitespace);
+            w.text = collapse_whitespace(&w.text);
+            if ends_with_space {
+                w.text.push(' ');
+            }
+            w
+        })
+        .collect();
+
+    if let Some(last) = words.last_mut() {
+        last.text = last.text.trim_end().to_string();
+    }
+    (!words.is_empty()).then_some(words)
+}
+
+// <head> <translations>/<transliterations>: itunes:key -> text
+fn head_texts(root: Node, kind: &str) -> HashMap<String, String> {
+    let mut texts = HashMap::new();
+    for entry in root.descendants().filter(|n| n.tag_name().name() == kind) {
+        for text in entry.children().filter(|n| n.tag_name().name() == "text") {
+            if let Some(key) = attr(text, "for") {
+                texts.insert(key.to_string(), collapse_whitespace(&node_text(text)));
+            }
+        }
+    }
+    texts
+}
+
+// Attribute by local name, ignoring the namespace prefix (ttm:, itunes:, xml:)
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes().find(|a| a.name() == name).map(|a| a.value())
+}
+
+// All text under a node, skipping nested translation/romanization spans
+fn node_text(node: Node) -> String {
+    let mut text = String::new();
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(child.text().unwrap_or(""));
+        } else if child.is_element()
+            && !matches!(attr(child, "role"), Some("x-translation") | Some("x-roman"))
+        {
+            text.push_str(&node_text(child));
+        }
+    }
+    text
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// TTML time expressions -> milliseconds:
+// clock "hh:mm:ss.fff", "mm:ss.fff", "ss.fff", or offset "1.5s", "1500ms", "2m", "1h"
+pub fn parse_time(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let offset = |number: &str, scale: f64| -> Option<i64> {
+        number
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|n| (n * scale).round() as i64)
+    };
+
+    if let Some(ms) = value.strip_suffix("ms") {
+        return offset(ms, 1.0);
+    }
+    if let Some(s) = value.strip_suffix('s') {
+        return offset(s, 1000.0);
+    }
+    if let Some(m) = value.strip_suffix('m') {
+        return offset(m, 60_000.0);
+    }
+    if let Some(h) = value.strip_suffix('h') {
+        return offset(h, 3_600_000.0);
+    }
+
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let mut seconds = 0.0;
+    for part in &parts {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    }
+    Some((seconds * 1000.0).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::empty_track;
+
+    const TTML: &str = r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttm="http://www.w3.org/ns/ttml#metadata" xmlns:itunes="http://music.apple.com/lyric-ttml-internal" itunes:timing="Word">
+  <head><metadata><iTunesMetadata xmlns="http://music.apple.com/lyric-ttml-internal">
+    <translations><translation xml:lang="en"><text for="L1">Hello world</text></translation></translations>
+  </iTunesMetadata></metadata></head>
+  <body dur="3:01.500"><div>
+    <p begin="1.000" end="3.500" itunes:key="L1" ttm:agent="v1"><span begin="1.000" end="1.500">Hel</span><span begin="1.500" end="2.000">lo</span> <span begin="2.000" end="3.000">world</span><span ttm:role="x-bg"><span begin="3.0" end="3.5">(ooh)</span></span></p>
+    <p begin="00:00:04.000" end="5s" ttm:agent="v2">Line two<span ttm:role="x-translation" xml:lang="en">Trans two</span></p>
+  </div></body>
+</tt>"#;
+
+    #[test]
+    fn time_expressions() {
+        assert_eq!(parse_time("1.5s"), Some(1500));
+        assert_eq!(parse_time("1500ms"), Some(1500));
+        assert_eq!(parse_time("2m"), Some(120_000));
+        assert_eq!(parse_time("3.25"), Some(3250));
+        assert_eq!(parse_time("01:02.5"), Some(62_500));
+        assert_eq!(parse_time("1:00:00.000"), Some(3_600_000));
+        assert_eq!(parse_time("soon"), None);
+    }
+
+    #[test]
+    fn word_timing_and_duration() {
+        let lyrics_data = parse_ttml(TTML, empty_track()).unwrap();
+        assert_eq!(lyrics_data.track.duration, 181_500);
+        assert!(lyrics_data.is_synced);
+
+        let line = &lyrics_data.lyrics[0];
+        assert_eq!(line.text, "Hello world");
+        let words = line.words.as_ref().unwrap();
+        assert_eq!(words.iter().map(|w| w.text.as_str()).collect::<String>(), line.text);
+        assert_eq!((words[2].start_time, words[2].end_time), (2000, Some(3000)));
+        assert_eq!(line.singer.as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn background_vocals() {
+        let lyrics_data = parse_ttml(TTML, empty_track()).unwrap();
+        let background = lyrics_data.lyrics[0].background.as_ref().unwrap();
+        assert_eq!(background.text, "(ooh)");
+        assert_eq!(background.words.as_ref().unwrap()[0].start_time, 3000);
+    }
+
+    #[test]
+    fn translations() {
+        let lyrics_data = parse_ttml(TTML, empty_track()).unwrap();
+        // From the head, keyed by itunes:key, and from an inline x-translation span
+        assert_eq!(lyrics_data.lyrics[0].trans_text.as_deref(), Some("Hello world"));
+        assert_eq!(lyrics_data.lyrics[1].text, "Line two");
+        assert_eq!(lyrics_data.lyrics[1].trans_text.as_deref(), Some("Trans two"));
+        assert_eq!((lyrics_data.lyrics[1].start_time, lyrics_data.lyrics[1].end_time), (4000, Some(5000)));
+    }
+
+    #[test]
+    fn rejects_other_xml() {
+        assert!(parse_ttml("<html><p>Hi</p></html>", empty_track()).is_err());
+        assert!(parse_ttml("<tt", empty_track()).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{config, LyricLine};
+
+const INTERLUDE_CONFIG_FILE: &str = "interlude.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InterludeConfig {
+    pub enabled: bool,
+    pub threshold_ms: i64, // Shortest gap shown as an instrumental break
+}
+
+impl Default for InterludeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_ms: 10_000,
+        }
+    }
+}
+
+pub fn load_interlude_config() -> InterludeConfig {
+    config::load_json(INTERLUDE_CONFIG_FILE)
+}
+
+pub fn save_interlude_config(interlude: &InterludeConfig) -> Result<(), String> {
+    config::save_json(INTERLUDE_CONFIG_FILE, interlude)
+}
+
+// Instrumental break the playback position is currently in
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interlude {
+    pub start_time: i64,
+    pub end_time: i64,        // When the next line starts
+    pub remaining: i64,       // ms until the next line, for the countdown
+    pub next_line_index: usize,
+}
+
+// The intro before the first line or a gap between a line's end and the next line,
+// when `position` (ms) falls inside one at least `threshold` ms long
+pub fn find_interlude(lyrics: &[LyricLine], position: i64, threshold: i64) -> Option<Interlude> {
+    let next_line_index = lyrics.iter().position(|line| line.start_time > position)?;
+    let end_time = lyrics[next_line_index].start_time;
+
+    let start_time = match next_line_index {
+        0 => 0,
+        i => lyrics[i - 1].end_time?,
+    };
+
+    if position < start_time || end_time - start_time < threshold {
+        return None;
+    }
+
+    Some(Interlude {
+        start_time,
+        end_time,
+        remaining: end_time - position,
+        next_line_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(start_time: i64, end_time: Option<i64>) -> LyricLine {
+        LyricLine {
+            start_time,
+            end_time,
+            text: "la".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn lyrics() -> Vec<LyricLine> {
+        vec![line(12_000, Some(15_000)), line(16_000, Some(20_000)), line(40_000, None), line(41_000, None)]
+    }
+
+    #[test]
+    fn intro_counts_down_from_zero() {
+        let interlude = find_interlude(&lyrics(), 2000, 10_000).unwrap();
+        assert_eq!((interlude.start_time, interlude.end_time), (0, 12_000));
+        assert_eq!((interlude.remaining, interlude.next_line_index), (10_000, 0));
+    }
+
+    #[test]
+    fn gap_boundaries() {
+        let lyrics = lyrics();
+        // Still singing the previous line
+        assert!(find_interlude(&lyrics, 19_999, 10_000).is_none());
+        let interlude = find_interlude(&lyrics, 20_000, 10_000).unwrap();
+        assert_eq!((interlude.start_time, interlude.end_time, interlude.remaining), (20_000, 40_000, 20_000));
+        assert_eq!(find_interlude(&lyrics, 39_999, 10_000).unwrap().remaining, 1);
+        // The next line has started
+        assert!(find_interlude(&lyrics, 40_000, 10_000).is_none());
+    }
+
+    #[test]
+    fn short_gaps_are_not_interludes() {
+        let lyrics = lyrics();
+        assert!(find_interlude(&lyrics, 15_500, 10_000).is_none());
+        assert!(find_interlude(&lyrics, 25_000, 20_001).is_none());
+        assert!(find_interlude(&lyrics, 25_000, 20_000).is_some());
+    }
+
+    #[test]
+    fn no_interlude_without_an_end_time_or_after_the_last_line() {
+        let lyrics = lyrics();
+        assert!(find_interlude(&lyrics, 40_500, 0).is_none());
+        assert!(find_interlude(&lyrics, 50_000, 0).is_none());
+    }
+}
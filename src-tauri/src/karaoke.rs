@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+use crate::LyricLine;
+
+// Position within the active line's words, for karaoke highlighting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordProgress {
+    pub line_index: usize,
+    pub word_index: usize, // Words before this one are fully sung
+    pub progress: f64,     // 0.0-1.0 through the active word
+}
+
+// Word progress at `position` (ms), or None when the active line has no word timing
+pub fn word_progress(lyrics: &[LyricLine], position: i64) -> Option<WordProgress> {
+    let line_index = lyrics.iter().rposition(|line| line.start_time <= position)?;
+    let line = &lyrics[line_index];
+    let words = line.words.as_deref().filter(|w| !w.is_empty())?;
+
+    let Some(word_index) = words.iter().rposition(|word| word.start_time <= position) else {
+        return Some(WordProgress {
+            line_index,
+            word_index: 0,
+            progress: 0.0,
+        });
+    };
+
+    let word = &words[word_index];
+    let end = word
+        .end_time
+        .or_else(|| words.get(word_index + 1).map(|next| next.start_time))
+        .or(line.end_time)
+        .or_else(|| lyrics.get(line_index + 1).map(|next| next.start_time));
+
+    let progress = match end {
+        Some(end) if end > word.start_time => {
+            ((position - word.start_time) as f64 / (end - word.start_time) as f64).clamp(0.0, 1.0)
+        }
+        _ => 1.0,
+    };
+
+    Some(WordProgress {
+        line_index,
+        word_index,
+        progress,
+    })
+}
@@ -1,9 +1,12 @@
 use axum::{
-    routing::post,
+    routing::{get, post},
     Json, Router,
-    http::Method,
+    http::{Method, StatusCode},
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Runtime, Manager, PhysicalPosition};
@@ -16,61 +19,36 @@ use windows::Win32::UI::WindowsAndMessaging::{GetCursorPos, SetWindowPos, HWND_T
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::{POINT, HWND};
 
-// Track info from Spotify
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TrackInfo {
-    pub title: String,
-    pub artist: String,
-    pub album: String,
-    pub album_art: Option<String>,
-    pub duration: u64,
-}
-
-// Single lyric line
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LyricLine {
-    pub start_time: i64,
-    pub end_time: Option<i64>,
-    pub text: String,           // Original text
-    #[serde(default)]
-    pub pron_text: Option<String>,  // Phonetic/romanized text
-    #[serde(default)]
-    pub trans_text: Option<String>, // Translation text
-}
-
-// Full lyrics data payload
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LyricsData {
-    pub track: TrackInfo,
-    pub lyrics: Vec<LyricLine>,
-    pub is_synced: bool,
-}
-
-// Progress sync data
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ProgressData {
-    pub position: u64,
-    pub is_playing: bool,
-    #[serde(default)]
-    pub duration: Option<u64>,
-    #[serde(default)]
-    pub remaining: Option<f64>,
-    #[serde(default)]
-    pub next_track: Option<NextTrackInfo>,
-}
-
-// Next track info for preview
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NextTrackInfo {
-    pub title: String,
-    pub artist: String,
-    pub album_art: Option<String>,
-}
+mod bidi;
+mod chinese_script;
+mod config;
+mod control;
+mod formats;
+mod interlude;
+mod karaoke;
+mod line_overrides;
+mod local_lyrics;
+mod lyrics_cache;
+mod network;
+mod normalize;
+mod profanity;
+mod relay;
+mod romanize;
+mod ruby;
+mod script;
+mod track_key;
+mod track_offsets;
+mod translate;
+mod translations;
+
+use network::NetworkConfig;
+use relay::{RelayConfig, RelayHandle};
+
+// Payload types shared with senders
+pub use ivlyrics_protocol::{
+    BackgroundVocals, LyricLine, LyricWord, LyricsData, NextTrackInfo, ProgressData, RubySegment, Script, TextDirection,
+    TrackInfo,
+};
 
 // Events to emit to frontend
 #[derive(Debug, Clone, Serialize)]
@@ -79,15 +57,284 @@ pub struct LyricsEvent {
     pub lyrics_data: LyricsData,
 }
 
+// Where the displayed lyrics came from, when not straight from the sender
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricsStatusEvent {
+    pub source: String,            // "local" or "cache"
+    pub file_name: Option<String>, // Local file that replaced the sender's lyrics
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProgressEvent {
     pub progress_data: ProgressData,
+    pub word_progress: Option<karaoke::WordProgress>, // Karaoke position, when the line has word timing
 }
 
 // Shared state for HTTP server
 struct AppState<R: Runtime> {
     app_handle: AppHandle<R>,
+    relay: Option<RelayHandle>, // Upstream overlay to forward to, when relay mode is on
+    display_locally: bool,
+    current_lyrics: Mutex<Option<LyricsData>>, // Last lyrics sent to the frontend
+    lyrics_cache: lyrics_cache::LyricsCache,
+    track_offsets: track_offsets::TrackOffsets,
+    normalize: Mutex<normalize::NormalizeConfig>,
+    interlude: Mutex<interlude::InterludeConfig>,
+    romanize: Mutex<romanize::RomanizeConfig>,
+    chinese_script: Mutex<chinese_script::ChineseScript>,
+    in_interlude: AtomicBool, // Whether the last progress update was inside an instrumental break
+    translations: Mutex<translations::TranslationsConfig>,
+    translation: translate::Translation,
+    line_overrides: line_overrides::LineOverrides,
+    profanity: Mutex<profanity::ProfanityConfig>,
+}
+
+impl<R: Runtime> AppState<R> {
+    // Sender lyrics: fill in from local files when the sender has nothing synced, then publish
+    fn ingest_lyrics(self: &Arc<Self>, mut lyrics_data: LyricsData) {
+        if lyrics_data.lyrics.is_empty() || !lyrics_data.is_synced {
+            if let Some(folder) = local_lyrics::load_lyrics_folder() {
+                if let Some((local, file_name)) = local_lyrics::load_local_lyrics(&folder, &lyrics_data.track) {
+                    if local.is_synced || lyrics_data.lyrics.is_empty() {
+                        lyrics_data.lyrics = local.lyrics;
+                        lyrics_data.is_synced = local.is_synced;
+                        let _ = self.app_handle.emit(
+                            "lyrics-status",
+                            LyricsStatusEvent {
+                                source: "local".to_string(),
+                                file_name: Some(file_name),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        self.forward_lyrics(&lyrics_data);
+        let job = self.process_lyrics(&mut lyrics_data);
+        self.publish_lyrics(lyrics_data, job);
+    }
+
+    // Cleanup and per-track offset applied to lyrics from any source before publishing;
+    // returns the lines left to machine-translate, taken before explicit words are masked
+    fn process_lyrics(&self, lyrics_data: &mut LyricsData) -> Option<translate::TranslationJob> {
+        if let Ok(rules) = self.normalize.lock() {
+            normalize::normalize_lyrics(lyrics_data, &rules);
+        }
+        ruby::apply_ruby(lyrics_data);
+        if let Ok(script) = self.chinese_script.lock() {
+            chinese_script::convert_lyrics(lyrics_data, *script);
+        }
+        if let Ok(romanize) = self.romanize.lock() {
+            romanize::fill_pronunciation(lyrics_data, &romanize);
+        }
+        if let Ok(languages) = self.translations.lock() {
+            translations::select_translations(lyrics_data, &languages);
+        }
+        self.translation.apply_cached(lyrics_data);
+        self.line_overrides.apply(lyrics_data);
+        // Translate and cache the sung text, not the masked copy on screen
+        let job = self.translation.missing(lyrics_data);
+        if let Ok(profanity) = self.profanity.lock() {
+            profanity::mask_lyrics(lyrics_data, &profanity);
+        }
+        script::tag_lyrics(lyrics_data);
+        bidi::apply_bidi(lyrics_data);
+
+        let offset = self.track_offsets.get(&lyrics_data.track);
+        if offset != 0 {
+            track_offsets::shift_lyrics(&mut lyrics_data.lyrics, offset);
+        }
+        job
+    }
+
+    // Shift the current track's lyrics by `step` ms and remember it for future plays
+    fn nudge_track_offset(self: &Arc<Self>, step: i64) -> Result<i64, String> {
+        let lyrics_data = {
+            let mut current = self.current_lyrics.lock().map_err(|e| e.to_string())?;
+            let lyrics_data = current.as_mut().ok_or_else(|| "No track playing".to_string())?;
+            track_offsets::shift_lyrics(&mut lyrics_data.lyrics, step);
+            lyrics_data.clone()
+        };
+
+        let offset = self.track_offsets.adjust(&lyrics_data.track, step)?;
+        self.publish_lyrics(lyrics_data, None);
+        let _ = self.app_handle.emit("track-offset", offset);
+        Ok(offset)
+    }
+
+    // Masked copy of text added after the lyrics were processed, or None when nothing matched
+    fn mask_text(&self, text: &str) -> Option<String> {
+        self.profanity
+            .lock()
+            .ok()
+            .and_then(|profanity| profanity::mask_text(text, &profanity))
+    }
+
+    // Save a user correction for the current track's line at `start_time` (as displayed)
+    // and show it right away
+    fn override_line(self: &Arc<Self>, start_time: i64, line_override: line_overrides::LineOverride) -> Result<(), String> {
+        let track = self.current_track().ok_or_else(|| "No track playing".to_string())?;
+        // Overrides are stored in the sender's timing, before the track's offset
+        let offset = self.track_offsets.get(&track);
+        self.line_overrides.set(&track, start_time - offset, line_override.clone())?;
+
+        let lyrics_data = {
+            let mut current = self.current_lyrics.lock().map_err(|e| e.to_string())?;
+            let lyrics_data = current.as_mut().ok_or_else(|| "No track playing".to_string())?;
+            for line in lyrics_data.lyrics.iter_mut().filter(|l| l.start_time == start_time) {
+                if let Some(pron_text) = &line_override.pron_text {
+                    let masked = self.mask_text(pron_text);
+                    lyrics_data.track.profanity_masked |= masked.is_some();
+                    let (pron_text, direction) = bidi::directed(masked.as_deref().unwrap_or(pron_text));
+                    line.pron_text = Some(pron_text);
+                    line.pron_direction = direction;
+                }
+                if let Some(trans_text) = &line_override.trans_text {
+                    let masked = self.mask_text(trans_text);
+                    lyrics_data.track.profanity_masked |= masked.is_some();
+                    let (trans_text, direction) = bidi::directed(masked.as_deref().unwrap_or(trans_text));
+                    line.trans_text = Some(trans_text);
+                    line.trans_direction = direction;
+                }
+            }
+            lyrics_data.clone()
+        };
+        self.publish_lyrics(lyrics_data, None);
+        Ok(())
+    }
+
+    // Forward lyrics as received, before processing: the upstream overlay runs its own
+    // pipeline (offsets, generated rows, masking) and must not get ours applied twice
+    fn forward_lyrics(&self, lyrics_data: &LyricsData) {
+        if let Some(relay) = &self.relay {
+            relay.forward_lyrics(lyrics_data);
+        }
+    }
+
+    // Remember as current, emit to frontend, then translate what's missing
+    fn publish_lyrics(self: &Arc<Self>, lyrics_data: LyricsData, job: Option<translate::TranslationJob>) {
+        if let Ok(mut current) = self.current_lyrics.lock() {
+            *current = Some(lyrics_data.clone());
+        }
+
+        if self.display_locally {
+            let _ = self.app_handle.emit("lyrics-update", LyricsEvent { lyrics_data });
+            if let Some(job) = job {
+                self.translate_missing(job);
+            }
+        }
+    }
+
+    // Machine-translate the job's lines in the background, patch them (masked) into the
+    // current lyrics and emit "translations-update"
+    fn translate_missing(self: &Arc<Self>, job: translate::TranslationJob) {
+        let state = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let results = match state.translation.run(&job).await {
+                Ok(results) => results,
+                Err(e) => {
+                    eprintln!("Translation failed: {}", e);
+                    return;
+                }
+            };
+
+            let mut lines = Vec::new();
+            let mut profanity_masked = false;
+            if let Ok(mut current) = state.current_lyrics.lock() {
+                let Some(lyrics_data) = current.as_mut() else {
+                    return;
+                };
+                for (line_index, text, translation) in results {
+                    // Skip lines that changed (new track) or got a translation meanwhile;
+                    // the job has the sung text, the current lyrics its masked copy
+                    let Some(line) = lyrics_data.lyrics.get_mut(line_index) else {
+                        continue;
+                    };
+                    let shown = state.mask_text(&text).unwrap_or(text);
+                    if line.trans_text.is_some() || bidi::strip_isolates(&line.text) != shown {
+                        continue;
+                    }
+                    let masked = state.mask_text(&translation);
+                    lyrics_data.track.profanity_masked |= masked.is_some();
+                    let (trans_text, trans_direction) = bidi::directed(masked.as_deref().unwrap_or(&translation));
+                    line.trans_text = Some(trans_text.clone());
+                    line.trans_direction = trans_direction;
+                    lines.push(translate::LineTranslation {
+                        line_index,
+                        trans_text,
+                        trans_direction,
+                    });
+                }
+                profanity_masked = lyrics_data.track.profanity_masked;
+            }
+
+            if !lines.is_empty() {
+                let _ = state.app_handle.emit(
+                    "translations-update",
+                    translate::TranslationsEvent {
+                        target_language: job.target_language,
+                        lines,
+                        profanity_masked,
+                    },
+                );
+            }
+        });
+    }
+
+    fn publish_progress(&self, mut progress_data: ProgressData) {
+        if let Some(relay) = &self.relay {
+            relay.forward_progress(&progress_data);
+        }
+
+        // Compensate for time spent in relays on the way here
+        if let Some(offset) = progress_data.relay_offset.take() {
+            if progress_data.is_playing {
+                progress_data.position += offset;
+            }
+        }
+
+        if self.display_locally {
+            let position = progress_data.position as i64;
+            let (word_progress, interlude) = self
+                .current_lyrics
+                .lock()
+                .ok()
+                .and_then(|current| {
+                    let lyrics = &current.as_ref().filter(|l| l.is_synced)?.lyrics;
+                    Some((karaoke::word_progress(lyrics, position), self.find_interlude(lyrics, position)))
+                })
+                .unwrap_or((None, None));
+
+            // Countdown while inside a break, then a single null when it ends
+            let was_in_interlude = self.in_interlude.swap(interlude.is_some(), Ordering::Relaxed);
+            if interlude.is_some() || was_in_interlude {
+                let _ = self.app_handle.emit("interlude", interlude);
+            }
+
+            let _ = self.app_handle.emit(
+                "progress-update",
+                ProgressEvent { progress_data, word_progress },
+            );
+        }
+    }
+
+    fn find_interlude(&self, lyrics: &[LyricLine], position: i64) -> Option<interlude::Interlude> {
+        let config = self.interlude.lock().ok()?;
+        if !config.enabled {
+            return None;
+        }
+        interlude::find_interlude(lyrics, position, config.threshold_ms)
+    }
+
+    fn current_track(&self) -> Option<TrackInfo> {
+        self.current_lyrics
+            .lock()
+            .ok()
+            .and_then(|current| current.as_ref().map(|l| l.track.clone()))
+    }
 }
 
 // HTTP Server port state
@@ -115,6 +362,8 @@ struct TrayStrings {
     reset_pos: &'static str,
     toggle_lock: &'static str,
     devtools: &'static str,
+    lan_warning: &'static str,
+    lan_bind_error: &'static str,
 }
 
 fn get_tray_strings(lang: &str) -> TrayStrings {
@@ -125,6 +374,8 @@ fn get_tray_strings(lang: &str) -> TrayStrings {
             reset_pos: "위치 초기화",
             toggle_lock: "잠금 전환",
             devtools: "개발자 도구",
+            lan_warning: "⚠ LAN 모드 켜짐",
+            lan_bind_error: "⚠ 잘못된 LAN 주소, 로컬 전용",
         },
         _ => TrayStrings {
             quit: "Quit",
@@ -132,6 +383,8 @@ fn get_tray_strings(lang: &str) -> TrayStrings {
             reset_pos: "Reset Position",
             toggle_lock: "Lock/Unlock",
             devtools: "DevTools",
+            lan_warning: "⚠ LAN mode on",
+            lan_bind_error: "⚠ Invalid LAN address, local only",
         }
     }
 }
@@ -228,23 +481,87 @@ async fn handle_lyrics<R: Runtime>(
     axum::extract::State(state): axum::extract::State<Arc<AppState<R>>>,
     Json(lyrics_data): Json<LyricsData>,
 ) -> &'static str {
-    // Emit to frontend
-    let _ = state.app_handle.emit("lyrics-update", LyricsEvent { lyrics_data });
+    // Ingest may read local files
+    let _ = tauri::async_runtime::spawn_blocking(move || {
+        state.lyrics_cache.store(&lyrics_data);
+        state.ingest_lyrics(lyrics_data);
+    })
+    .await;
     "OK"
 }
 
+// Track reference for repeat plays: 404 asks the sender to upload the full payload
+async fn handle_lyrics_ref<R: Runtime>(
+    axum::extract::State(state): axum::extract::State<Arc<AppState<R>>>,
+    Json(track): Json<TrackInfo>,
+) -> StatusCode {
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(mut lyrics_data) = state.lyrics_cache.get(&track) else {
+            return StatusCode::NOT_FOUND;
+        };
+        // Keep the sender's fresh metadata (album art etc.)
+        lyrics_data.track = track;
+        let _ = state.app_handle.emit(
+            "lyrics-status",
+            LyricsStatusEvent {
+                source: "cache".to_string(),
+                file_name: None,
+            },
+        );
+        state.ingest_lyrics(lyrics_data);
+        StatusCode::OK
+    })
+    .await
+    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
 async fn handle_progress<R: Runtime>(
     axum::extract::State(state): axum::extract::State<Arc<AppState<R>>>,
     Json(progress_data): Json<ProgressData>,
 ) -> &'static str {
-    // Emit to frontend
-    let _ = state.app_handle.emit("progress-update", ProgressEvent { progress_data });
+    state.publish_progress(progress_data);
     "OK"
 }
 
+// Lyrics file content in the body, e.g. POST /lyrics/lrc
+async fn handle_lyrics_file<R: Runtime>(
+    axum::extract::State(state): axum::extract::State<Arc<AppState<R>>>,
+    axum::extract::Path(format): axum::extract::Path<String>,
+    body: String,
+) -> Result<&'static str, (StatusCode, String)> {
+    let format = formats::LyricsFormat::from_extension(&format)
+        .ok_or((StatusCode::NOT_FOUND, format!("Unsupported lyrics format: {}", format)))?;
+    let track = state.current_track().unwrap_or_else(formats::empty_track);
+    let mut lyrics_data = formats::parse_lyrics(&body, format, track)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+
+    state.forward_lyrics(&lyrics_data);
+    let job = state.process_lyrics(&mut lyrics_data);
+    state.publish_lyrics(lyrics_data, job);
+    Ok("OK")
+}
+
+// Schema endpoint: list of type names, or the JSON Schema of one payload type
+async fn handle_schema_index() -> Json<&'static [&'static str]> {
+    Json(ivlyrics_protocol::SCHEMA_TYPES)
+}
+
+async fn handle_schema(
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let name = name.trim_end_matches(".json");
+    ivlyrics_protocol::schema_for(name)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
 // Start HTTP server with custom port
-async fn start_http_server<R: Runtime>(app_handle: AppHandle<R>, port: u16) {
-    let state = Arc::new(AppState { app_handle: app_handle.clone() });
+async fn start_http_server<R: Runtime>(state: Arc<AppState<R>>, port: u16, network: NetworkConfig) {
+    let app_handle = state.app_handle.clone();
+    let addrs = network.listen_addrs(port);
+    if let Some(error) = network.bind_error() {
+        eprintln!("{}; listening on loopback only", error);
+    }
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -253,21 +570,41 @@ async fn start_http_server<R: Runtime>(app_handle: AppHandle<R>, port: u16) {
 
     let app = Router::new()
         .route("/lyrics", post(handle_lyrics::<R>))
+        .route("/lyrics/ref", post(handle_lyrics_ref::<R>))
+        .route("/lyrics/:format", post(handle_lyrics_file::<R>))
         .route("/progress", post(handle_progress::<R>))
+        .route("/schema", get(handle_schema_index))
+        .route("/schema/:name", get(handle_schema))
+        .nest("/command", control::command_router::<R>(network.access_token.clone()))
+        .layer(axum::middleware::from_fn_with_state(Arc::new(network), network::guard_access))
         .layer(cors)
         .with_state(state);
 
-    let addr = format!("127.0.0.1:{}", port);
-    let listener = tokio::net::TcpListener::bind(&addr)
+    let listener = tokio::net::TcpListener::bind(&addrs[0])
         .await
-        .expect(&format!("Failed to bind to port {}", port));
-
-    println!("HTTP server listening on http://{}", addr);
+        .unwrap_or_else(|e| panic!("Failed to bind to {}: {}", addrs[0], e));
+    println!("HTTP server listening on http://{}", addrs[0]);
+
+    // Extra listeners are best effort: a dual-stack [::] may already cover loopback
+    for addr in &addrs[1..] {
+        match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => {
+                println!("HTTP server listening on http://{}", addr);
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
+                        eprintln!("HTTP server failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("Failed to bind to {}: {}", addr, e),
+        }
+    }
 
     // Emit port info to frontend
     let _ = app_handle.emit("server-port", port);
 
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .expect("HTTP server failed");
 }
@@ -308,9 +645,307 @@ async fn set_server_port(port: u16) -> Result<(), String> {
     }
 }
 
+// Tauri command to get LAN listening settings
+#[tauri::command]
+async fn get_network_config() -> Result<NetworkConfig, String> {
+    Ok(network::load_network_config())
+}
+
+// Tauri command to save LAN listening settings (applied after restart)
+#[tauri::command]
+async fn set_network_config(mut config: NetworkConfig) -> Result<NetworkConfig, String> {
+    if config.lan_enabled && config.access_token.trim().is_empty() {
+        config.access_token = network::generate_access_token()?;
+    }
+    network::save_network_config(&config)?;
+    Ok(config)
+}
+
+// Tauri command to replace the LAN access token (applied after restart)
+#[tauri::command]
+async fn regenerate_access_token() -> Result<String, String> {
+    let mut config = network::load_network_config();
+    config.access_token = network::generate_access_token()?;
+    network::save_network_config(&config)?;
+    Ok(config.access_token)
+}
+
+// Tauri command to get relay settings
+#[tauri::command]
+async fn get_relay_config() -> Result<RelayConfig, String> {
+    Ok(relay::load_relay_config())
+}
+
+// Tauri command to save relay settings (applied after restart)
+#[tauri::command]
+async fn set_relay_config(config: RelayConfig) -> Result<(), String> {
+    relay::save_relay_config(&config)
+}
+
+// Tauri command to load a local lyrics file and show it like sender lyrics
+#[tauri::command]
+async fn import_lyrics_file(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    path: String,
+) -> Result<LyricsData, String> {
+    let track = state.current_track().unwrap_or_else(formats::empty_track);
+    let mut lyrics_data = formats::parse_lyrics_file(Path::new(&path), track)?;
+    state.forward_lyrics(&lyrics_data);
+    let job = state.process_lyrics(&mut lyrics_data);
+    state.publish_lyrics(lyrics_data.clone(), job);
+    Ok(lyrics_data)
+}
+
+// Tauri command to save the current lyrics as LRC, SRT or ASS at a user-chosen path
+#[tauri::command]
+async fn export_lyrics(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    path: String,
+    format: Option<String>,
+) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    let format = format
+        .as_deref()
+        .or_else(|| path.extension().and_then(|ext| ext.to_str()))
+        .and_then(formats::export::ExportFormat::from_extension)
+        .ok_or_else(|| "Unsupported export format (use lrc, srt or ass)".to_string())?;
+
+    let lyrics_data = state
+        .current_lyrics
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| "No lyrics to export".to_string())?;
+
+    let content = formats::export::export_lyrics(&lyrics_data, format)?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Tauri command to get the local lyrics folder
+#[tauri::command]
+async fn get_lyrics_folder() -> Result<Option<String>, String> {
+    Ok(local_lyrics::load_lyrics_folder().map(|p| p.to_string_lossy().to_string()))
+}
+
+// Tauri command to set the local lyrics folder (empty to disable)
+#[tauri::command]
+async fn set_lyrics_folder(folder: String) -> Result<(), String> {
+    local_lyrics::save_lyrics_folder(&folder)
+}
+
+// Tauri command to delete all cached lyrics
+#[tauri::command]
+async fn clear_lyrics_cache(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<(), String> {
+    state.lyrics_cache.clear()
+}
+
+// Tauri command to get the current track's remembered timing offset (ms)
+#[tauri::command]
+async fn get_track_offset(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<i64, String> {
+    Ok(state
+        .current_track()
+        .map(|track| state.track_offsets.get(&track))
+        .unwrap_or(0))
+}
+
+// Tauri command to nudge the current track's lyrics later (+) or earlier (-) by step ms
+#[tauri::command]
+async fn nudge_track_offset(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    step: i64,
+) -> Result<i64, String> {
+    state.nudge_track_offset(step)
+}
+
+// Tauri command to clear the current track's timing offset
+#[tauri::command]
+async fn reset_track_offset(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<i64, String> {
+    let track = state.current_track().ok_or_else(|| "No track playing".to_string())?;
+    let offset = state.track_offsets.get(&track);
+    state.nudge_track_offset(-offset)
+}
+
+// Tauri command to get the lyrics cleanup rules
+#[tauri::command]
+async fn get_normalize_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<normalize::NormalizeConfig, String> {
+    state.normalize.lock().map(|rules| rules.clone()).map_err(|e| e.to_string())
+}
+
+// Tauri command to save the lyrics cleanup rules (applied to the next lyrics received)
+#[tauri::command]
+async fn set_normalize_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    config: normalize::NormalizeConfig,
+) -> Result<(), String> {
+    normalize::save_normalize_config(&config)?;
+    *state.normalize.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+// Tauri command to get the instrumental break settings
+#[tauri::command]
+async fn get_interlude_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<interlude::InterludeConfig, String> {
+    state.interlude.lock().map(|config| config.clone()).map_err(|e| e.to_string())
+}
+
+// Tauri command to save the instrumental break settings
+#[tauri::command]
+async fn set_interlude_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    config: interlude::InterludeConfig,
+) -> Result<(), String> {
+    interlude::save_interlude_config(&config)?;
+    *state.interlude.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+// Tauri command to get which scripts get generated pronunciations
+#[tauri::command]
+async fn get_romanize_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<romanize::RomanizeConfig, String> {
+    state.romanize.lock().map(|config| config.clone()).map_err(|e| e.to_string())
+}
+
+// Tauri command to save which scripts get generated pronunciations
+#[tauri::command]
+async fn set_romanize_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    config: romanize::RomanizeConfig,
+) -> Result<(), String> {
+    romanize::save_romanize_config(&config)?;
+    *state.romanize.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+// Tauri command to get the preferred Chinese script ("original", "simplified" or "traditional")
+#[tauri::command]
+async fn get_chinese_script(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<chinese_script::ChineseScript, String> {
+    state.chinese_script.lock().map(|script| *script).map_err(|e| e.to_string())
+}
+
+// Tauri command to save the preferred Chinese script (applied to the next lyrics received)
+#[tauri::command]
+async fn set_chinese_script(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    script: chinese_script::ChineseScript,
+) -> Result<(), String> {
+    chinese_script::save_chinese_script(script)?;
+    *state.chinese_script.lock().map_err(|e| e.to_string())? = script;
+    Ok(())
+}
+
+// Tauri command to get the preferred translation languages
+#[tauri::command]
+async fn get_translations_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<translations::TranslationsConfig, String> {
+    state.translations.lock().map(|config| config.clone()).map_err(|e| e.to_string())
+}
+
+// Tauri command to save the preferred translation languages (applied to the next lyrics received)
+#[tauri::command]
+async fn set_translations_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    config: translations::TranslationsConfig,
+) -> Result<(), String> {
+    let config = translations::save_translations_config(&config)?;
+    *state.translations.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+// Tauri command to get the machine translation settings
+#[tauri::command]
+async fn get_translate_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<translate::TranslateConfig, String> {
+    Ok(state.translation.config())
+}
+
+// Tauri command to save the machine translation settings
+#[tauri::command]
+async fn set_translate_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    config: translate::TranslateConfig,
+) -> Result<(), String> {
+    state.translation.set_config(config)
+}
+
+// Tauri command to delete all cached machine translations
+#[tauri::command]
+async fn clear_translation_cache(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<(), String> {
+    state.translation.clear_cache()
+}
+
+// Tauri command to correct pron_text and/or trans_text of the current track's line at
+// start_time; omitted fields keep their value, an empty string hides the row,
+// and both omitted removes the correction (from the next time the track plays)
+#[tauri::command]
+async fn set_line_override(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    start_time: i64,
+    pron_text: Option<String>,
+    trans_text: Option<String>,
+) -> Result<(), String> {
+    state.override_line(start_time, line_overrides::LineOverride { pron_text, trans_text })
+}
+
+// Tauri command to list every saved line correction, grouped by track
+#[tauri::command]
+async fn list_line_overrides(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<Vec<line_overrides::TrackOverridesEntry>, String> {
+    state.line_overrides.list()
+}
+
+// Tauri command to delete one line's correction, or all of a track's when start_time is omitted
+#[tauri::command]
+async fn delete_line_override(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    key: String,
+    start_time: Option<i64>,
+) -> Result<(), String> {
+    state.line_overrides.delete(&key, start_time)
+}
+
+// Tauri command to get the profanity filter settings
+#[tauri::command]
+async fn get_profanity_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+) -> Result<profanity::ProfanityConfig, String> {
+    state.profanity.lock().map(|config| config.clone()).map_err(|e| e.to_string())
+}
+
+// Tauri command to save the profanity filter settings (applied to the next lyrics received)
+#[tauri::command]
+async fn set_profanity_config(
+    state: tauri::State<'_, Arc<AppState<tauri::Wry>>>,
+    config: profanity::ProfanityConfig,
+) -> Result<(), String> {
+    profanity::save_profanity_config(&config)?;
+    *state.profanity.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
 // Tauri command to restart the application
 #[tauri::command]
 fn restart_app(app_handle: tauri::AppHandle) {
+    flush_caches(&app_handle);
     app_handle.restart();
     // Note: restart() does not return, so no Ok() needed
 }
@@ -408,29 +1043,7 @@ fn get_system_fonts() -> Result<Vec<String>, String> {
 
 // Load server port from config file or environment variable
 fn load_server_port() -> u16 {
-    // Try environment variable first
-    if let Ok(port_str) = std::env::var("IVLYRICS_PORT") {
-        if let Ok(port) = port_str.parse::<u16>() {
-            if port >= 1024 {
-                return port;
-            }
-        }
-    }
-
-    // Try config file
-    if let Some(config_dir) = dirs::config_dir() {
-        let config_path = config_dir.join("ivlyrics-overlay").join("port.txt");
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(port) = content.trim().parse::<u16>() {
-                if port >= 1024 {
-                    return port;
-                }
-            }
-        }
-    }
-
-    // Default port
-    15000
+    ivlyrics_protocol::discover_port()
 }
 
 fn reset_window_if_offscreen<R: Runtime>(
@@ -616,6 +1229,15 @@ fn show_or_create_settings_window<R: Runtime, M: Manager<R>>(manager: &M) -> Res
 pub fn run() {
     // Load server port
     let server_port = load_server_port();
+    let mut network_config = network::load_network_config();
+
+    // The command API always needs a token, so make sure one exists
+    if network_config.access_token.is_empty() {
+        if let Ok(token) = network::generate_access_token() {
+            network_config.access_token = token;
+            let _ = network::save_network_config(&network_config);
+        }
+    }
     let start_minimized = load_start_minimized_setting();
 
     // Shared state for HTTP server port
@@ -658,6 +1280,18 @@ pub fn run() {
             let devpanel_i = MenuItem::with_id(app, "devpanel", tray_strings.devtools, true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&toggle_lock_i, &settings_i, &reset_pos_i, &devpanel_i, &quit_i])?;
 
+            // Keep LAN exposure visible while it is on, and say why it isn't when the
+            // bind address is invalid
+            let lan_addr = network_config.listen_addrs(server_port)[0].clone();
+            let lan_status = match network_config.bind_error() {
+                Some(_) => format!("{} ({})", tray_strings.lan_bind_error, network_config.bind_address),
+                None => format!("{} ({})", tray_strings.lan_warning, lan_addr),
+            };
+            if network_config.lan_enabled {
+                let lan_warning_i = MenuItem::with_id(app, "lan_warning", &lan_status, false, None::<&str>)?;
+                menu.prepend(&lan_warning_i)?;
+            }
+
             // Get tray icon - use default_window_icon with proper error handling
             let tray_icon = app.default_window_icon()
                 .cloned()
@@ -671,32 +1305,23 @@ pub fn run() {
                 });
 
 
+            let tray_tooltip = if network_config.lan_enabled {
+                format!("Lyrics Plus Overlay - {}", lan_status)
+            } else {
+                "Lyrics Plus Overlay".to_string()
+            };
+
             let _tray = TrayIconBuilder::with_id("main-tray")
                 .icon(tray_icon)
+                .tooltip(tray_tooltip)
                 .menu(&menu)
                 .show_menu_on_left_click(true)
                 .on_menu_event(|app, event| {
                     match event.id.as_ref() {
                         "quit" => app.exit(0),
-                        "reset_pos" => {
-                             if let Some(window) = app.get_webview_window("main") {
-                                 let _ = window.set_position(PhysicalPosition::new(100, 100));
-                                 let _ = window.show();
-                                 let _ = window.set_focus();
-                             }
-                        },
-                        "settings" => {
-                            let _ = show_or_create_settings_window(app);
-                        },
-                        "toggle_lock" => {
-                             let state = app.state::<std::sync::Arc<std::sync::Mutex<AppLockState>>>();
-                             let mut lock_state = state.lock().unwrap();
-                             lock_state.is_locked = !lock_state.is_locked;
-                             let new_locked = lock_state.is_locked;
-                             
-                             // Emit event to frontend to update UI
-                             let _ = app.emit("lock-state-update", new_locked);
-                        },
+                        "reset_pos" => control::reset_position(app),
+                        "settings" => control::open_settings(app),
+                        "toggle_lock" => control::toggle_lock(app),
                         "devpanel" => {
                             #[cfg(debug_assertions)]
                             {
@@ -763,11 +1388,42 @@ pub fn run() {
                 reset_window_if_offscreen(&window, 100, 100);
             }
 
+            // Shared lyrics state for the HTTP server and commands
+            let relay_config = relay::load_relay_config();
+            let relay = relay::start_relay(app_handle.clone(), &relay_config);
+            let http_state = Arc::new(AppState {
+                app_handle: app_handle.clone(),
+                display_locally: relay.is_none() || relay_config.display_locally,
+                relay,
+                current_lyrics: Mutex::new(None),
+                lyrics_cache: lyrics_cache::LyricsCache::open(),
+                track_offsets: track_offsets::TrackOffsets::load(),
+                normalize: Mutex::new(normalize::load_normalize_config()),
+                interlude: Mutex::new(interlude::load_interlude_config()),
+                romanize: Mutex::new(romanize::load_romanize_config()),
+                chinese_script: Mutex::new(chinese_script::load_chinese_script()),
+                in_interlude: AtomicBool::new(false),
+                translations: Mutex::new(translations::load_translations_config()),
+                translation: translate::Translation::load(),
+                line_overrides: line_overrides::LineOverrides::load(),
+                profanity: Mutex::new(profanity::load_profanity_config()),
+            });
+            app.manage(http_state.clone());
+
+            // Save new machine translations in the background instead of after every job
+            let flush_state = http_state.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(translate::FLUSH_INTERVAL).await;
+                    flush_state.translation.flush();
+                }
+            });
+
             // Start HTTP server in background with custom port
-            let app_handle_http = app_handle.clone();
             let http_port = server_port;
+            let http_network = network_config.clone();
             tauri::async_runtime::spawn(async move {
-                start_http_server(app_handle_http, http_port).await;
+                start_http_server(http_state, http_port, http_network).await;
             });
 
             // Auto-open settings window on startup (unless startMinimized is enabled)
@@ -1042,12 +1698,55 @@ pub fn run() {
             get_system_fonts,
             get_server_port,
             set_server_port,
+            get_network_config,
+            set_network_config,
+            regenerate_access_token,
+            get_relay_config,
+            set_relay_config,
+            import_lyrics_file,
+            export_lyrics,
+            get_lyrics_folder,
+            set_lyrics_folder,
+            clear_lyrics_cache,
+            get_track_offset,
+            nudge_track_offset,
+            reset_track_offset,
+            get_normalize_config,
+            set_normalize_config,
+            get_interlude_config,
+            set_interlude_config,
+            get_romanize_config,
+            set_romanize_config,
+            get_chinese_script,
+            set_chinese_script,
+            get_translations_config,
+            set_translations_config,
+            get_translate_config,
+            set_translate_config,
+            clear_translation_cache,
+            set_line_override,
+            list_line_overrides,
+            delete_line_override,
+            get_profanity_config,
+            set_profanity_config,
             restart_app,
             set_tray_language,
             get_start_minimized,
             set_start_minimized
         ])
 
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                flush_caches(app_handle);
+            }
+        });
+}
+
+// Write out what is only saved periodically, before the app exits or restarts
+fn flush_caches(app_handle: &AppHandle) {
+    if let Some(state) = app_handle.try_state::<Arc<AppState<tauri::Wry>>>() {
+        state.translation.flush();
+    }
 }
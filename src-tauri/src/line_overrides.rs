@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::track_key::TrackKey;
+use crate::{config, LyricsData, TrackInfo};
+
+const LINE_OVERRIDES_FILE: &str = "line_overrides.json";
+
+// User corrections for one line; None keeps what the sender or generator provided,
+// an empty string hides the row
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LineOverride {
+    pub pron_text: Option<String>,
+    pub trans_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TrackOverrides {
+    title: String, // For listing only; matching uses the track identity
+    artist: String,
+    lines: BTreeMap<i64, LineOverride>, // Keyed by the line's start time in the sender's timing (ms)
+}
+
+// Overrides of one track for the settings list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackOverridesEntry {
+    pub key: String,
+    pub title: String,
+    pub artist: String,
+    pub lines: Vec<LineOverrideEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineOverrideEntry {
+    pub start_time: i64,
+    #[serde(flatten)]
+    pub line: LineOverride,
+}
+
+// Per-line pron_text/trans_text corrections keyed by track identity and line start time
+pub struct LineOverrides {
+    overrides: Mutex<HashMap<String, TrackOverrides>>,
+}
+
+impl LineOverrides {
+    pub fn load() -> Self {
+        Self {
+            overrides: Mutex::new(config::load_json(LINE_OVERRIDES_FILE)),
+        }
+    }
+
+    // Replace pron_text/trans_text of lines that have an override; run before offsets are applied
+    pub fn apply(&self, lyrics_data: &mut LyricsData) {
+        let digest = TrackKey::identity(&lyrics_data.track).digest();
+        let Ok(overrides) = self.overrides.lock() else {
+            return;
+        };
+        let Some(track) = overrides.get(&digest) else {
+            return;
+        };
+        for line in &mut lyrics_data.lyrics {
+            if let Some(line_override) = track.lines.get(&line.start_time) {
+                if let Some(pron_text) = &line_override.pron_text {
+                    line.pron_text = Some(pron_text.clone());
+                }
+                if let Some(trans_text) = &line_override.trans_text {
+                    line.trans_text = Some(trans_text.clone());
+                }
+            }
+        }
+    }
+
+    // Set or clear (both fields None) the override of the line starting at `start_time`
+    pub fn set(&self, track: &TrackInfo, start_time: i64, line_override: LineOverride) -> Result<(), String> {
+        let digest = TrackKey::identity(track).digest();
+        let mut overrides = self.overrides.lock().map_err(|e| e.to_string())?;
+
+        if line_override.pron_text.is_none() && line_override.trans_text.is_none() {
+            if let Some(entry) = overrides.get_mut(&digest) {
+                entry.lines.remove(&start_time);
+                if entry.lines.is_empty() {
+                    overrides.remove(&digest);
+                }
+            }
+        } else {
+            let entry = overrides.entry(digest).or_default();
+            entry.title = track.title.clone();
+            entry.artist = track.artist.clone();
+            entry.lines.insert(start_time, line_override);
+        }
+        config::save_json(LINE_OVERRIDES_FILE, &*overrides)
+    }
+
+    pub fn list(&self) -> Result<Vec<TrackOverridesEntry>, String> {
+        let overrides = self.overrides.lock().map_err(|e| e.to_string())?;
+        let mut entries: Vec<TrackOverridesEntry> = overrides
+            .iter()
+            .map(|(key, track)| TrackOverridesEntry {
+                key: key.clone(),
+                title: track.title.clone(),
+                artist: track.artist.clone(),
+                lines: track
+                    .lines
+                    .iter()
+                    .map(|(start_time, line)| LineOverrideEntry {
+                        start_time: *start_time,
+                        line: line.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.artist, &a.title).cmp(&(&b.artist, &b.title)));
+        Ok(entries)
+    }
+
+    // Delete one line's override, or every override of the track when `start_time` is None
+    pub fn delete(&self, key: &str, start_time: Option<i64>) -> Result<(), String> {
+        let mut overrides = self.overrides.lock().map_err(|e| e.to_string())?;
+        match start_time {
+            Some(start_time) => {
+                if let Some(entry) = overrides.get_mut(key) {
+                    entry.lines.remove(&start_time);
+                    if entry.lines.is_empty() {
+                        overrides.remove(key);
+                    }
+                }
+            }
+            None => {
+                overrides.remove(key);
+            }
+        }
+        config::save_json(LINE_OVERRIDES_FILE, &*overrides)
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+
+use crate::formats::{self, LyricsFormat};
+use crate::{config, track_key, LyricsData, TrackInfo};
+
+// Richest formats first when several files match
+const EXTENSIONS: &[&str] = &["ttml", "lrc", "srt", "vtt"];
+const MAX_DEPTH: usize = 3;
+
+// Load lyrics folder setting from config file
+pub fn load_lyrics_folder() -> Option<PathBuf> {
+    let dir = ivlyrics_protocol::config_dir()?;
+    let content = std::fs::read_to_string(dir.join("lyrics_folder.txt")).ok()?;
+    let folder = content.trim();
+    (!folder.is_empty()).then(|| PathBuf::from(folder))
+}
+
+// Save lyrics folder setting to config file (empty clears it)
+pub fn save_lyrics_folder(folder: &str) -> Result<(), String> {
+    let folder = folder.trim();
+    if !folder.is_empty() && !Path::new(folder).is_dir() {
+        return Err(format!("Not a folder: {}", folder));
+    }
+    let config_path = config::app_config_dir()?.join("lyrics_folder.txt");
+    std::fs::write(&config_path, folder)
+        .map_err(|e| format!("Failed to save lyrics folder config: {}", e))
+}
+
+// Matching local file parsed into lyrics for `track`, with the file name
+pub fn load_local_lyrics(folder: &Path, track: &TrackInfo) -> Option<(LyricsData, String)> {
+    let path = find_local_lyrics(folder, track)?;
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    match formats::parse_lyrics_file(&path, track.clone()) {
+        Ok(lyrics_data) => Some((lyrics_data, file_name)),
+        Err(e) => {
+            eprintln!("Failed to load local lyrics {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// File named "Artist - Title", "Title - Artist" or "Title", compared after normalization
+pub fn find_local_lyrics(folder: &Path, track: &TrackInfo) -> Option<PathBuf> {
+    let title = track_key::normalize(&track.title);
+    if title.is_empty() {
+        return None;
+    }
+
+    let artist = track_key::normalize(&track.artist);
+    let primary = track_key::normalize(track_key::primary_artist(&track.artist));
+    // Better matches first
+    let candidates = [
+        format!("{}{}", artist, title),
+        format!("{}{}", primary, title),
+        format!("{}{}", title, artist),
+        format!("{}{}", title, primary),
+        title.clone(),
+    ];
+
+    let mut files = Vec::new();
+    collect_lyrics_files(folder, 0, &mut files);
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let stem = track_key::normalize(path.file_stem()?.to_str()?);
+            let rank = candidates.iter().position(|c| *c == stem)?;
+            let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+            let ext_rank = EXTENSIONS.iter().position(|e| *e == ext)?;
+            Some(((rank, ext_rank), path))
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, path)| path)
+}
+
+fn collect_lyrics_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                collect_lyrics_files(&path, depth + 1, files);
+            }
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(LyricsFormat::from_extension)
+            .is_some()
+        {
+            files.push(path);
+        }
+    }
+}
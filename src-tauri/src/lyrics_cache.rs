@@ -0,0 +1,102 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::track_key::TrackKey;
+use crate::{config, LyricsData, TrackInfo};
+
+const CACHE_DIR: &str = "lyrics-cache";
+const MAX_CACHE_BYTES: u64 = 50 * 1024 * 1024;
+
+// Sender lyrics stored per track (<config>/ivlyrics-overlay/lyrics-cache/<digest>.json),
+// least recently used entries evicted once the folder grows past MAX_CACHE_BYTES
+pub struct LyricsCache {
+    dir: Option<PathBuf>,
+}
+
+impl LyricsCache {
+    pub fn open() -> Self {
+        let dir = config::app_config_dir().ok().map(|dir| dir.join(CACHE_DIR));
+        if let Some(dir) = &dir {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        Self { dir }
+    }
+
+    fn entry_path(&self, track: &TrackInfo) -> Option<PathBuf> {
+        let digest = TrackKey::from_track(track).digest();
+        self.dir.as_ref().map(|dir| dir.join(format!("{}.json", digest)))
+    }
+
+    pub fn get(&self, track: &TrackInfo) -> Option<LyricsData> {
+        let path = self.entry_path(track)?;
+        let content = std::fs::read_to_string(&path).ok()?;
+        let lyrics_data = serde_json::from_str(&content).ok()?;
+
+        // Mark as recently used for eviction
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(lyrics_data)
+    }
+
+    pub fn store(&self, lyrics_data: &LyricsData) {
+        if lyrics_data.lyrics.is_empty() {
+            return;
+        }
+        let Some(path) = self.entry_path(&lyrics_data.track) else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string(lyrics_data) else {
+            return;
+        };
+        if let Err(e) = std::fs::write(&path, json) {
+            eprintln!("Failed to write lyrics cache: {}", e);
+            return;
+        }
+        self.evict();
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())?.flatten() {
+            let _ = std::fs::remove_file(entry.path());
+        }
+        Ok(())
+    }
+
+    fn evict(&self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                meta.is_file().then(|| (modified, meta.len(), entry.path()))
+            })
+            .collect();
+
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        if total <= MAX_CACHE_BYTES {
+            return;
+        }
+
+        files.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in files {
+            if total <= MAX_CACHE_BYTES {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total -= len;
+            }
+        }
+    }
+}
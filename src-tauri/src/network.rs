@@ -0,0 +1,218 @@
+use axum::{
+    extract::{ConnectInfo, Query, Request, State},
+    http::{HeaderMap, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::config;
+
+const NETWORK_CONFIG_FILE: &str = "network.json";
+
+// Listening mode for the local HTTP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkConfig {
+    pub lan_enabled: bool,        // Bind bind_address instead of loopback
+    pub bind_address: String,     // Interface IP, or 0.0.0.0 for all interfaces
+    pub allowed_ips: Vec<String>, // Client IPs or CIDR ranges; empty allows private-network addresses only
+    pub access_token: String,     // Required from every non-loopback client and for /command routes
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            lan_enabled: false,
+            bind_address: "0.0.0.0".to_string(),
+            allowed_ips: Vec::new(),
+            access_token: String::new(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    // Addresses the server should bind, given the configured port; the first one is
+    // the main listener. A LAN interface IP is bound next to loopback, so the local
+    // sender posting to 127.0.0.1 keeps working. An address that doesn't parse
+    // binds loopback only (see bind_error) rather than every interface.
+    pub fn listen_addrs(&self, port: u16) -> Vec<String> {
+        let loopback = format!("127.0.0.1:{}", port);
+        if !self.lan_enabled {
+            return vec![loopback];
+        }
+        match self.bind_address.trim().parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) if ip.is_unspecified() => vec![format!("0.0.0.0:{}", port)],
+            Ok(IpAddr::V4(ip)) if ip.is_loopback() => vec![loopback],
+            Ok(IpAddr::V6(ip)) => vec![format!("[{}]:{}", ip, port), loopback],
+            Ok(ip) => vec![format!("{}:{}", ip, port), loopback],
+            Err(_) => vec![loopback],
+        }
+    }
+
+    // Why LAN mode is on but only loopback is bound
+    pub fn bind_error(&self) -> Option<String> {
+        (self.lan_enabled && self.bind_address.trim().parse::<IpAddr>().is_err())
+            .then(|| format!("Invalid bind address: {}", self.bind_address))
+    }
+
+    // Without an allowlist only private-network clients (10/8, 172.16/12, 192.168/16,
+    // link-local, fc00::/7) get through, never a public address
+    fn is_ip_allowed(&self, ip: IpAddr) -> bool {
+        if self.allowed_ips.is_empty() {
+            return is_private(ip);
+        }
+        self.allowed_ips.iter().any(|entry| ip_matches(entry.trim(), ip))
+    }
+}
+
+pub fn load_network_config() -> NetworkConfig {
+    config::load_json(NETWORK_CONFIG_FILE)
+}
+
+pub fn save_network_config(network: &NetworkConfig) -> Result<(), String> {
+    for entry in &network.allowed_ips {
+        if parse_ip_range(entry.trim()).is_none() {
+            return Err(format!("Invalid IP or CIDR range: {}", entry));
+        }
+    }
+    if let Some(error) = network.bind_error() {
+        return Err(error);
+    }
+    config::save_json(NETWORK_CONFIG_FILE, network)
+}
+
+// Random 128-bit token, hex encoded
+pub fn generate_access_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Token sent with a request: `Authorization: Bearer`, `X-Access-Token`, or `?token=`
+// (percent-decoded)
+pub fn request_token(headers: &HeaderMap, uri: &Uri) -> Option<String> {
+    if let Some(value) = headers.get("authorization").and_then(|v| v.to_str().ok()) {
+        if let Some(token) = value.strip_prefix("Bearer ") {
+            return Some(token.trim().to_string());
+        }
+    }
+    if let Some(value) = headers.get("x-access-token").and_then(|v| v.to_str().ok()) {
+        return Some(value.trim().to_string());
+    }
+    let Query(mut query) = Query::<HashMap<String, String>>::try_from_uri(uri).ok()?;
+    query.remove("token")
+}
+
+// Compare without short-circuiting on the first differing byte
+pub fn token_matches(expected: &str, given: Option<&str>) -> bool {
+    let Some(given) = given else {
+        return false;
+    };
+    if expected.is_empty() || expected.len() != given.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(given.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+// Middleware for every route: loopback passes, LAN clients need the allowlist and token
+pub async fn guard_access(
+    State(network): State<Arc<NetworkConfig>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = peer.ip().to_canonical();
+    if ip.is_loopback() {
+        return next.run(request).await;
+    }
+
+    if !network.lan_enabled || !network.is_ip_allowed(ip) {
+        return (StatusCode::FORBIDDEN, "Client not allowed").into_response();
+    }
+
+    let token = request_token(request.headers(), request.uri());
+    if !token_matches(&network.access_token, token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, "Invalid access token").into_response();
+    }
+
+    next.run(request).await
+}
+
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => ["fc00::/7", "fe80::/10"].iter().any(|range| ip_matches(range, IpAddr::V6(ip))),
+    }
+}
+
+fn ip_matches(entry: &str, ip: IpAddr) -> bool {
+    let Some((network, prefix)) = parse_ip_range(entry) else {
+        return false;
+    };
+    match (network, ip) {
+        (IpAddr::V4(net), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(net) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(net), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(net) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+// "192.168.0.12" or "192.168.0.0/24" -> (network, prefix length)
+fn parse_ip_range(entry: &str) -> Option<(IpAddr, u32)> {
+    let (addr, prefix) = match entry.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix.parse::<u32>().ok()?)),
+        None => (entry, None),
+    };
+    let ip = addr.parse::<IpAddr>().ok()?.to_canonical();
+    let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(max_prefix);
+    (prefix <= max_prefix).then_some((ip, prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lan(bind_address: &str) -> NetworkConfig {
+        NetworkConfig {
+            lan_enabled: true,
+            bind_address: bind_address.to_string(),
+            ..NetworkConfig::default()
+        }
+    }
+
+    #[test]
+    fn invalid_bind_address_fails_closed() {
+        assert_eq!(lan("192.168.0.300").listen_addrs(8080), ["127.0.0.1:8080"]);
+        assert!(lan("192.168.0.300").bind_error().is_some());
+        assert_eq!(lan("192.168.0.12").listen_addrs(8080), ["192.168.0.12:8080", "127.0.0.1:8080"]);
+        assert_eq!(lan("0.0.0.0").bind_error(), None);
+    }
+
+    #[test]
+    fn query_token_is_percent_decoded() {
+        let uri: Uri = "/command/lock?x=1&token=a%2Bb%26c%25".parse().unwrap();
+        assert_eq!(request_token(&HeaderMap::new(), &uri).as_deref(), Some("a+b&c%"));
+    }
+
+    #[test]
+    fn empty_allowlist_admits_private_addresses_only() {
+        let network = lan("0.0.0.0");
+        assert!(network.is_ip_allowed("192.168.1.20".parse().unwrap()));
+        assert!(network.is_ip_allowed("fe80::1".parse().unwrap()));
+        assert!(!network.is_ip_allowed("8.8.8.8".parse().unwrap()));
+    }
+}
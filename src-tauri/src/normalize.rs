@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{config, LyricLine, LyricsData};
+
+const NORMALIZE_CONFIG_FILE: &str = "normalize.json";
+
+// Cleanup rules applied to every LyricsData before it is emitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NormalizeConfig {
+    pub trim_whitespace: bool,        // Trim text, pron_text and trans_text
+    pub sort_lines: bool,             // Order lines by start_time
+    pub dedupe_lines: bool,           // Drop repeats of the same text at the same timestamp
+    #[serde(alias = "markInstrumentalGaps")]
+    pub drop_spacer_lines: bool, // Drop spacer lines (empty, ♪, (Instrumental)), ending the previous line there
+    pub fill_end_times: bool,         // Missing end_time runs until the next line starts
+    pub drop_redundant_text: bool,    // Drop pron_text/trans_text that just repeat the text
+}
+
+impl Default for NormalizeConfig {
+    fn default() -> Self {
+        Self {
+            trim_whitespace: true,
+            sort_lines: true,
+            dedupe_lines: true,
+            drop_spacer_lines: true,
+            fill_end_times: true,
+            drop_redundant_text: true,
+        }
+    }
+}
+
+pub fn load_normalize_config() -> NormalizeConfig {
+    config::load_json(NORMALIZE_CONFIG_FILE)
+}
+
+pub fn save_normalize_config(normalize: &NormalizeConfig) -> Result<(), String> {
+    config::save_json(NORMALIZE_CONFIG_FILE, normalize)
+}
+
+pub fn normalize_lyrics(lyrics_data: &mut LyricsData, rules: &NormalizeConfig) {
+    let lyrics = &mut lyrics_data.lyrics;
+
+    for line in lyrics.iter_mut() {
+        if rules.trim_whitespace {
+            trim_line(line);
+        }
+        if rules.drop_redundant_text {
+            drop_redundant_text(line);
+        }
+    }
+
+    // The rest is about timing; unsynced lyrics keep their blank stanza breaks
+    if !lyrics_data.is_synced {
+        return;
+    }
+
+    if rules.sort_lines {
+        lyrics.sort_by_key(|line| line.start_time);
+    }
+
+    if rules.dedupe_lines {
+        lyrics.dedup_by(|line, prev| line.start_time == prev.start_time && line.text == prev.text);
+    }
+
+    // Drop end times that end before they start
+    for line in lyrics.iter_mut() {
+        if line.end_time.is_some_and(|end| end <= line.start_time) {
+            line.end_time = None;
+        }
+    }
+
+    // The previous line ends where the spacer started, so the break stays as a gap
+    if rules.drop_spacer_lines {
+        let mut kept: Vec<LyricLine> = Vec::with_capacity(lyrics.len());
+        for line in lyrics.drain(..) {
+            if is_spacer(&line) {
+                if let Some(prev) = kept.last_mut() {
+                    if prev.end_time.is_none() && line.start_time > prev.start_time {
+                        prev.end_time = Some(line.start_time);
+                    }
+                }
+                continue;
+            }
+            kept.push(line);
+        }
+        *lyrics = kept;
+    }
+
+    if rules.fill_end_times {
+        let track_end = lyrics_data.track.duration as i64;
+        for i in 0..lyrics.len() {
+            if lyrics[i].end_time.is_some() {
+                continue;
+            }
+            let start = lyrics[i].start_time;
+            let end = lyrics
+                .get(i + 1)
+                .map(|next| next.start_time)
+                .or((track_end > 0).then_some(track_end))
+                .filter(|end| *end > start);
+            lyrics[i].end_time = end;
+        }
+    }
+}
+
+fn trim_line(line: &mut LyricLine) {
+    line.text = line.text.trim().to_string();
+    for extra in [&mut line.pron_text, &mut line.trans_text] {
+        if let Some(text) = extra.as_mut() {
+            *text = text.trim().to_string();
+        }
+    }
+}
+
+fn drop_redundant_text(line: &mut LyricLine) {
+    let text = comparable(&line.text);
+    for extra in [&mut line.pron_text, &mut line.trans_text] {
+        if extra
+            .as_deref()
+            .is_some_and(|t| t.trim().is_empty() || comparable(t) == text)
+        {
+            *extra = None;
+        }
+    }
+}
+
+fn comparable(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// Blank lines and instrumental markers senders use to space out sections; "...",
+// "~~~" or a censored "****" are sung lines and stay
+fn is_spacer(line: &LyricLine) -> bool {
+    let text = line.text.trim();
+    if text.chars().all(|c| c.is_whitespace() || "♪♫♬♩".contains(c)) {
+        return true;
+    }
+    let inner = text
+        .trim_matches(|c| matches!(c, '(' | ')' | '[' | ']' | '<' | '>'))
+        .trim()
+        .to_lowercase();
+    matches!(inner.as_str(), "instrumental" | "interlude" | "간주" | "間奏" | "间奏")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackInfo;
+
+    fn line(start_time: i64, text: &str) -> LyricLine {
+        LyricLine {
+            start_time,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn synced(lyrics: Vec<LyricLine>) -> LyricsData {
+        LyricsData {
+            track: TrackInfo {
+                duration: 60_000,
+                ..Default::default()
+            },
+            lyrics,
+            is_synced: true,
+            ..Default::default()
+        }
+    }
+
+    fn none() -> NormalizeConfig {
+        NormalizeConfig {
+            trim_whitespace: false,
+            sort_lines: false,
+            dedupe_lines: false,
+            drop_spacer_lines: false,
+            fill_end_times: false,
+            drop_redundant_text: false,
+        }
+    }
+
+    fn normalized(lyrics: Vec<LyricLine>, rules: NormalizeConfig) -> Vec<(i64, Option<i64>, String)> {
+        let mut lyrics_data = synced(lyrics);
+        normalize_lyrics(&mut lyrics_data, &rules);
+        lyrics_data.lyrics.into_iter().map(|l| (l.start_time, l.end_time, l.text)).collect()
+    }
+
+    fn starts(lines: &[(i64, Option<i64>, String)]) -> Vec<i64> {
+        lines.iter().map(|(start, _, _)| *start).collect()
+    }
+
+    #[test]
+    fn sort_lines() {
+        let lyrics = vec![line(2000, "b"), line(1000, "a")];
+        let rules = NormalizeConfig { sort_lines: true, ..none() };
+        assert_eq!(starts(&normalized(lyrics.clone(), rules)), [1000, 2000]);
+        assert_eq!(starts(&normalized(lyrics, none())), [2000, 1000]);
+    }
+
+    #[test]
+    fn dedupe_lines() {
+        let lyrics = vec![line(1000, "a"), line(1000, "a"), line(1000, "b")];
+        let rules = NormalizeConfig { dedupe_lines: true, ..none() };
+        assert_eq!(normalized(lyrics.clone(), rules).len(), 2);
+        assert_eq!(normalized(lyrics, none()).len(), 3);
+    }
+
+    #[test]
+    fn fill_end_times() {
+        let lyrics = vec![line(1000, "a"), line(5000, "b")];
+        let rules = NormalizeConfig { fill_end_times: true, ..none() };
+        let ends: Vec<Option<i64>> = normalized(lyrics.clone(), rules).into_iter().map(|(_, end, _)| end).collect();
+        assert_eq!(ends, [Some(5000), Some(60_000)]);
+        assert!(normalized(lyrics, none()).iter().all(|(_, end, _)| end.is_none()));
+    }
+
+    #[test]
+    fn drop_spacer_lines() {
+        let lyrics = vec![
+            line(1000, "a"),
+            line(5000, "♪"),
+            line(20_000, "(Instrumental)"),
+            line(30_000, ""),
+            line(31_000, "****"),
+            line(32_000, "..."),
+            line(33_000, "~~~"),
+        ];
+        let rules = NormalizeConfig { drop_spacer_lines: true, ..none() };
+        let lines = normalized(lyrics.clone(), rules);
+        assert_eq!(starts(&lines), [1000, 31_000, 32_000, 33_000]);
+        assert_eq!(lines[0].1, Some(5000));
+        assert_eq!(normalized(lyrics, none()).len(), 7);
+    }
+
+    #[test]
+    fn drop_redundant_text() {
+        let mut lyrics = vec![line(1000, "Hello World")];
+        lyrics[0].pron_text = Some("hello  world".to_string());
+        lyrics[0].trans_text = Some("Bonjour".to_string());
+
+        let mut lyrics_data = synced(lyrics.clone());
+        normalize_lyrics(&mut lyrics_data, &NormalizeConfig { drop_redundant_text: true, ..none() });
+        assert_eq!(lyrics_data.lyrics[0].pron_text, None);
+        assert_eq!(lyrics_data.lyrics[0].trans_text.as_deref(), Some("Bonjour"));
+
+        let mut lyrics_data = synced(lyrics);
+        normalize_lyrics(&mut lyrics_data, &none());
+        assert!(lyrics_data.lyrics[0].pron_text.is_some());
+    }
+
+    #[test]
+    fn trim_whitespace() {
+        let mut lyrics = vec![line(1000, "  a ")];
+        lyrics[0].trans_text = Some(" b ".to_string());
+
+        let mut lyrics_data = synced(lyrics.clone());
+        normalize_lyrics(&mut lyrics_data, &NormalizeConfig { trim_whitespace: true, ..none() });
+        assert_eq!(lyrics_data.lyrics[0].text, "a");
+        assert_eq!(lyrics_data.lyrics[0].trans_text.as_deref(), Some("b"));
+
+        let mut lyrics_data = synced(lyrics);
+        normalize_lyrics(&mut lyrics_data, &none());
+        assert_eq!(lyrics_data.lyrics[0].text, "  a ");
+    }
+
+    #[test]
+    fn renamed_spacer_rule_reads_old_config() {
+        let rules: NormalizeConfig = serde_json::from_str(r#"{"markInstrumentalGaps": false}"#).unwrap();
+        assert!(!rules.drop_spacer_lines);
+    }
+}
@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+use crate::{config, LyricsData};
+
+const PROFANITY_CONFIG_FILE: &str = "profanity.json";
+
+const ENGLISH_WORDS: &str = include_str!("en.txt");
+const KOREAN_WORDS: &str = include_str!("ko.txt");
+
+// Endings that still match a listed English word: fucks, fucked, fucker, fuckin'
+const SUFFIXES: &[&str] = &["s", "es", "ed", "er", "ers", "ing", "in", "y"];
+
+// How a matched word is masked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaskStyle {
+    #[default]
+    Asterisks,   // ****
+    FirstLetter, // f***
+    Remove,      // Dropped along with a space next to it
+}
+
+// Masking explicit words in lyrics, e.g. while streaming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfanityConfig {
+    pub enabled: bool,
+    pub style: MaskStyle,
+    pub english: bool,             // Bundled English list
+    pub korean: bool,              // Bundled Korean list
+    pub custom_words: Vec<String>, // The user's own words or phrases; "!word" is never masked
+}
+
+impl Default for ProfanityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            style: MaskStyle::Asterisks,
+            english: true,
+            korean: true,
+            custom_words: Vec::new(),
+        }
+    }
+}
+
+pub fn load_profanity_config() -> ProfanityConfig {
+    config::load_json(PROFANITY_CONFIG_FILE)
+}
+
+pub fn save_profanity_config(profanity: &ProfanityConfig) -> Result<(), String> {
+    config::save_json(PROFANITY_CONFIG_FILE, profanity)
+}
+
+// Per-character lowercase, so indices in the lowered text match the original
+fn lowercase(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+// Scripts written without spaces between words, where endings attach directly
+fn is_unspaced(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{3041}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}')
+}
+
+// Letters and digits continue a word; kana, Hangul or Han next to Latin text start a new one
+fn continues_word(c: char) -> bool {
+    c.is_alphanumeric() && !is_unspaced(c)
+}
+
+struct Filter {
+    words: Vec<Vec<char>>,      // Matched as whole words, with SUFFIXES
+    fragments: Vec<Vec<char>>,  // Matched anywhere, e.g. Hangul followed by a particle
+    exceptions: Vec<Vec<char>>, // "!" entries: a match inside one of these is left alone
+}
+
+impl Filter {
+    fn new(profanity: &ProfanityConfig) -> Self {
+        let bundled = [(profanity.english, ENGLISH_WORDS), (profanity.korean, KOREAN_WORDS)];
+        let entries = bundled
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .flat_map(|(_, list)| list.lines().filter(|l| !l.starts_with('#')))
+            .chain(profanity.custom_words.iter().map(String::as_str))
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+
+        let (mut words, mut fragments, mut exceptions) = (Vec::new(), Vec::new(), Vec::new());
+        for entry in entries {
+            if let Some(exception) = entry.strip_prefix('!') {
+                if !exception.trim().is_empty() {
+                    exceptions.push(lowercase(exception.trim()));
+                }
+            } else if entry.chars().any(is_unspaced) {
+                fragments.push(lowercase(entry));
+            } else {
+                words.push(lowercase(entry));
+            }
+        }
+        Self {
+            words,
+            fragments,
+            exceptions,
+        }
+    }
+
+    // Character ranges to mask, sorted and non-overlapping
+    fn matches(&self, text: &str, style: MaskStyle) -> Vec<Range<usize>> {
+        let chars = lowercase(text);
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+
+        // Where the exceptions occur, e.g. 새끼손가락 around 새끼
+        let lowered: &[char] = &chars;
+        let allowed: Vec<Range<usize>> = (0..chars.len())
+            .flat_map(|i| {
+                self.exceptions
+                    .iter()
+                    .filter(move |exception| lowered[i..].starts_with(exception))
+                    .map(move |exception| i..i + exception.len())
+            })
+            .collect();
+
+        let mut i = 0;
+        while i < chars.len() {
+            let at_boundary = i == 0 || !continues_word(chars[i - 1]);
+            let end = self
+                .fragments
+                .iter()
+                .filter(|fragment| chars[i..].starts_with(fragment))
+                .map(|fragment| i + fragment.len())
+                .chain(
+                    self.words
+                        .iter()
+                        .filter(|_| at_boundary)
+                        .filter_map(|word| word_end(&chars, i, word)),
+                )
+                .filter(|&end| !allowed.iter().any(|range| range.start <= i && end <= range.end))
+                .max();
+            match end {
+                Some(end) => {
+                    ranges.push(i..end);
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+
+        if style == MaskStyle::Remove {
+            ranges = ranges.into_iter().map(|range| with_space(&chars, range)).collect();
+        }
+        ranges
+    }
+}
+
+// End of `word` (plus an ending) at `start` when it ends at a word boundary
+fn word_end(chars: &[char], start: usize, word: &[char]) -> Option<usize> {
+    if !chars[start..].starts_with(word) {
+        return None;
+    }
+    let end = start + word.len();
+    std::iter::once("")
+        .chain(SUFFIXES.iter().copied())
+        .map(|suffix| suffix.chars().collect::<Vec<char>>())
+        .filter(|suffix| chars[end..].starts_with(suffix))
+        .map(|suffix| end + suffix.len())
+        .filter(|&end| chars.get(end).is_none_or(|&c| !continues_word(c)))
+        .max()
+}
+
+// A removed word takes one neighbouring space with it, so no double space is left
+fn with_space(chars: &[char], range: Range<usize>) -> Range<usize> {
+    let space_before = range.start == 0 || chars[range.start - 1].is_whitespace();
+    if space_before && chars.get(range.end).is_some_and(|c| c.is_whitespace()) {
+        range.start..range.end + 1
+    } else if range.start > 0 && chars[range.start - 1].is_whitespace() && range.end == chars.len() {
+        range.start - 1..range.end
+    } else {
+        range
+    }
+}
+
+// Mask a piece of text whose first character is at `offset` in the text `ranges` refer to
+fn apply(piece: &str, offset: usize, ranges: &[Range<usize>], style: MaskStyle) -> String {
+    let mut out = String::with_capacity(piece.len());
+    for (i, c) in piece.chars().enumerate() {
+        let index = offset + i;
+        let Some(range) = ranges.iter().find(|range| range.contains(&index)) else {
+            out.push(c);
+            continue;
+        };
+        match style {
+            MaskStyle::Remove => {}
+            MaskStyle::FirstLetter if index == range.start => out.push(c),
+            _ if c.is_whitespace() => out.push(c),
+            _ => out.push('*'),
+        }
+    }
+    out
+}
+
+// Mask the pieces a text is made of (words, ruby bases) where the text itself is masked;
+// pieces that don't add up to the text are masked on their own
+fn apply_pieces<'a>(
+    filter: &Filter,
+    text: &str,
+    ranges: &[Range<usize>],
+    pieces: impl Iterator<Item = &'a mut String>,
+    style: MaskStyle,
+) -> Vec<bool> {
+    let pieces: Vec<&mut String> = pieces.collect();
+    let adds_up = pieces.iter().map(|piece| piece.as_str()).collect::<String>() == text;
+
+    let mut offset = 0;
+    let mut changed = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        let len = piece.chars().count();
+        let masked = if adds_up {
+            apply(piece, offset, ranges, style)
+        } else {
+            apply(piece, 0, &filter.matches(piece, style), style)
+        };
+        changed.push(masked != *piece);
+        *piece = masked;
+        offset += len;
+    }
+    changed
+}
+
+// Masked copy of a text, or None when nothing in it matched
+fn mask(filter: &Filter, text: &str, style: MaskStyle) -> Option<String> {
+    let ranges = filter.matches(text, style);
+    (!ranges.is_empty()).then(|| apply(text, 0, &ranges, style))
+}
+
+// Masked copy of a single text such as a late translation, or None when nothing matched
+pub fn mask_text(text: &str, profanity: &ProfanityConfig) -> Option<String> {
+    if !profanity.enabled {
+        return None;
+    }
+    mask(&Filter::new(profanity), text, profanity.style)
+}
+
+// Mask text, pron_text, trans_text and translations of every line, keeping words,
+// ruby and background vocals in step; marks the track when anything was masked
+pub fn mask_lyrics(lyrics_data: &mut LyricsData, profanity: &ProfanityConfig) {
+    if !profanity.enabled {
+        return;
+    }
+    let filter = Filter::new(profanity);
+    let style = profanity.style;
+    let mut masked = false;
+
+    for line in &mut lyrics_data.lyrics {
+        let ranges = filter.matches(&line.text, style);
+        if !ranges.is_empty() {
+            masked = true;
+            apply_pieces(&filter, &line.text, &ranges, line.words.iter_mut().flatten().map(|w| &mut w.text), style);
+            if let Some(segments) = line.ruby.as_mut() {
+                let changed = apply_pieces(&filter, &line.text, &ranges, segments.iter_mut().map(|s| &mut s.text), style);
+                // A reading would give the masked word away
+                for (segment, changed) in segments.iter_mut().zip(changed) {
+                    if changed {
+                        segment.reading = None;
+                    }
+                }
+            }
+            line.text = apply(&line.text, 0, &ranges, style);
+        }
+
+        if let Some(background) = line.background.as_mut() {
+            let ranges = filter.matches(&background.text, style);
+            if !ranges.is_empty() {
+                masked = true;
+                let words = background.words.iter_mut().flatten().map(|w| &mut w.text);
+                apply_pieces(&filter, &background.text, &ranges, words, style);
+                background.text = apply(&background.text, 0, &ranges, style);
+            }
+        }
+
+        let translations = line.translations.iter_mut().flat_map(|t| t.values_mut());
+        for text in [&mut line.pron_text, &mut line.trans_text].into_iter().flatten().chain(translations) {
+            if let Some(masked_text) = mask(&filter, text, style) {
+                *text = masked_text;
+                masked = true;
+            }
+        }
+    }
+
+    lyrics_data.track.profanity_masked |= masked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(custom_words: &[&str]) -> ProfanityConfig {
+        ProfanityConfig {
+            enabled: true,
+            custom_words: custom_words.iter().map(|w| w.to_string()).collect(),
+            ..ProfanityConfig::default()
+        }
+    }
+
+    #[test]
+    fn korean_compounds_are_not_masked() {
+        let profanity = config(&[]);
+        assert_eq!(mask_text("새끼손가락 걸고 약속해", &profanity), None);
+        assert_eq!(mask_text("언니미워", &profanity), None);
+        assert_eq!(mask_text("이 새끼야", &profanity).as_deref(), Some("이 **야"));
+        assert_eq!(mask_text("개새끼손", &profanity).as_deref(), Some("***손"));
+    }
+
+    #[test]
+    fn english_jot_is_not_masked() {
+        assert_eq!(mask_text("jot it down", &config(&[])), None);
+    }
+
+    #[test]
+    fn custom_exceptions() {
+        let profanity = config(&["바보", "!바보상자"]);
+        assert_eq!(mask_text("바보상자", &profanity), None);
+        assert_eq!(mask_text("바보야", &profanity).as_deref(), Some("**야"));
+    }
+}
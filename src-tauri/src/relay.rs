@@ -0,0 +1,154 @@
+use ivlyrics_protocol::OverlayClient;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::watch;
+
+use crate::{config, LyricsData, ProgressData};
+
+const RELAY_CONFIG_FILE: &str = "relay.json";
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(15);
+
+// Forwarding to another overlay instance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RelayConfig {
+    pub enabled: bool,
+    pub upstream_url: String,  // e.g. http://192.168.0.20:15000
+    pub access_token: String,  // Upstream LAN access token, if it requires one
+    pub display_locally: bool, // Keep showing lyrics on this instance too
+}
+
+pub fn load_relay_config() -> RelayConfig {
+    config::load_json(RELAY_CONFIG_FILE)
+}
+
+pub fn save_relay_config(relay: &RelayConfig) -> Result<(), String> {
+    let url = relay.upstream_url.trim();
+    if relay.enabled && !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("Invalid upstream URL: {}", relay.upstream_url));
+    }
+    config::save_json(RELAY_CONFIG_FILE, relay)
+}
+
+// Relay connection state for the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayStatusEvent {
+    pub connected: bool,
+    pub upstream_url: String,
+    pub error: Option<String>,
+}
+
+// Latest payloads waiting to be forwarded; older ones are superseded, not queued
+pub struct RelayHandle {
+    lyrics_tx: watch::Sender<Option<LyricsData>>,
+    progress_tx: watch::Sender<Option<(ProgressData, Instant)>>,
+}
+
+impl RelayHandle {
+    pub fn forward_lyrics(&self, lyrics_data: &LyricsData) {
+        let _ = self.lyrics_tx.send(Some(lyrics_data.clone()));
+    }
+
+    pub fn forward_progress(&self, progress_data: &ProgressData) {
+        let _ = self.progress_tx.send(Some((progress_data.clone(), Instant::now())));
+    }
+}
+
+// Spawn the forwarding task; returns None when relaying is off
+pub fn start_relay<R: Runtime>(app_handle: AppHandle<R>, relay: &RelayConfig) -> Option<RelayHandle> {
+    if !relay.enabled || relay.upstream_url.trim().is_empty() {
+        return None;
+    }
+
+    let (lyrics_tx, lyrics_rx) = watch::channel(None);
+    let (progress_tx, progress_rx) = watch::channel(None);
+    let client = OverlayClient::new(relay.upstream_url.trim())
+        .with_access_token(relay.access_token.trim())
+        .with_retries(0);
+
+    tauri::async_runtime::spawn(run_relay(app_handle, client, lyrics_rx, progress_rx));
+
+    Some(RelayHandle { lyrics_tx, progress_tx })
+}
+
+async fn run_relay<R: Runtime>(
+    app_handle: AppHandle<R>,
+    client: OverlayClient,
+    mut lyrics_rx: watch::Receiver<Option<LyricsData>>,
+    mut progress_rx: watch::Receiver<Option<(ProgressData, Instant)>>,
+) {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut connected: Option<bool> = None;
+    let mut lyrics_pending = false;
+    let mut progress_pending = false;
+    let mut last_latency = Duration::ZERO;
+
+    loop {
+        if !lyrics_pending && !progress_pending {
+            tokio::select! {
+                changed = lyrics_rx.changed() => {
+                    if changed.is_err() { return; }
+                    lyrics_pending = true;
+                }
+                changed = progress_rx.changed() => {
+                    if changed.is_err() { return; }
+                    progress_pending = true;
+                }
+            }
+        }
+        // Pick up anything else that arrived meanwhile
+        lyrics_pending |= lyrics_rx.has_changed().unwrap_or(false);
+        progress_pending |= progress_rx.has_changed().unwrap_or(false);
+
+        let mut result = Ok(());
+
+        // Lyrics go first so the upstream never gets progress for a track it hasn't seen
+        if lyrics_pending {
+            let lyrics_data = lyrics_rx.borrow_and_update().clone();
+            if let Some(lyrics_data) = lyrics_data {
+                result = client.send_lyrics(&lyrics_data).await;
+            }
+            lyrics_pending = result.is_err();
+        }
+
+        if result.is_ok() && progress_pending {
+            let latest = progress_rx.borrow_and_update().clone();
+            if let Some((mut progress_data, received_at)) = latest {
+                // Time held by this relay plus half the last round trip
+                let offset = received_at.elapsed() + last_latency / 2;
+                progress_data.relay_offset =
+                    Some(progress_data.relay_offset.unwrap_or(0) + offset.as_millis() as u64);
+
+                let sent_at = Instant::now();
+                result = client.send_progress(&progress_data).await;
+                if result.is_ok() {
+                    last_latency = sent_at.elapsed();
+                }
+            }
+            progress_pending = result.is_err();
+        }
+
+        let ok = result.is_ok();
+        if connected != Some(ok) {
+            connected = Some(ok);
+            let _ = app_handle.emit(
+                "relay-status",
+                RelayStatusEvent {
+                    connected: ok,
+                    upstream_url: client.base_url().to_string(),
+                    error: result.err(),
+                },
+            );
+        }
+
+        if ok {
+            backoff = INITIAL_BACKOFF;
+        } else {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
@@ -0,0 +1,275 @@
+// Revised Romanization of Korean, as pronounced: syllables are decomposed into
+// jamo, the main sound changes between syllables of a word are applied
+// (liaison, nasalization, lateralization, aspiration, palatalization),
+// then each jamo is spelled out.
+
+const SYLLABLE_BASE: u32 = 0xAC00;
+const SYLLABLE_LAST: u32 = 0xD7A3;
+
+const INITIALS: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+const VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we", "wi", "yu", "eu", "ui", "i",
+];
+
+const VOWEL_I: usize = 20;
+
+// Index 0 is "no final consonant"
+const FINALS: [Option<char>; 28] = [
+    None, Some('ㄱ'), Some('ㄲ'), Some('ㄳ'), Some('ㄴ'), Some('ㄵ'), Some('ㄶ'), Some('ㄷ'), Some('ㄹ'), Some('ㄺ'),
+    Some('ㄻ'), Some('ㄼ'), Some('ㄽ'), Some('ㄾ'), Some('ㄿ'), Some('ㅀ'), Some('ㅁ'), Some('ㅂ'), Some('ㅄ'), Some('ㅅ'),
+    Some('ㅆ'), Some('ㅇ'), Some('ㅈ'), Some('ㅊ'), Some('ㅋ'), Some('ㅌ'), Some('ㅍ'), Some('ㅎ'),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Syllable {
+    initial: char,
+    vowel: usize,
+    final_consonant: Option<char>,
+}
+
+pub fn is_hangul_syllable(c: char) -> bool {
+    (SYLLABLE_BASE..=SYLLABLE_LAST).contains(&(c as u32))
+}
+
+pub fn contains_hangul(text: &str) -> bool {
+    text.chars().any(is_hangul_syllable)
+}
+
+// Romanize every run of Hangul syllables; anything else is copied through
+pub fn romanize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut word: Vec<Syllable> = Vec::new();
+
+    for c in text.chars() {
+        if let Some(syllable) = decompose(c) {
+            word.push(syllable);
+            continue;
+        }
+        if !word.is_empty() {
+            out.push_str(&romanize_word(&mut word));
+            word.clear();
+        }
+        out.push(c);
+    }
+    if !word.is_empty() {
+        out.push_str(&romanize_word(&mut word));
+    }
+    out
+}
+
+fn decompose(c: char) -> Option<Syllable> {
+    if !is_hangul_syllable(c) {
+        return None;
+    }
+    let code = (c as u32 - SYLLABLE_BASE) as usize;
+    Some(Syllable {
+        initial: INITIALS[code / 588],
+        vowel: code % 588 / 28,
+        final_consonant: FINALS[code % 28],
+    })
+}
+
+fn romanize_word(word: &mut [Syllable]) -> String {
+    // ㄼ is said as ㄹ (넓다 neolda), except in 밟- where it is ㅂ (밟다 bapda)
+    // unless a vowel follows and carries it over (밟아 balba)
+    for i in 0..word.len() {
+        let before_vowel = word.get(i + 1).is_some_and(|next| next.initial == 'ㅇ');
+        let syllable = word[i];
+        if syllable.initial == 'ㅂ' && syllable.vowel == 0 && syllable.final_consonant == Some('ㄼ') && !before_vowel {
+            word[i].final_consonant = Some('ㅂ');
+        }
+    }
+
+    for i in 1..word.len() {
+        let next = word[i];
+        if let Some(final_consonant) = word[i - 1].final_consonant {
+            let (final_consonant, initial) = assimilate(final_consonant, next.initial, next.vowel);
+            word[i - 1].final_consonant = final_consonant;
+            word[i].initial = initial;
+        }
+    }
+
+    let mut out = String::new();
+    for (i, syllable) in word.iter().enumerate() {
+        let after_l = i > 0 && word[i - 1].final_consonant.map(coda) == Some('ㄹ');
+        out.push_str(initial_sound(syllable.initial, after_l));
+        out.push_str(VOWELS[syllable.vowel]);
+        if let Some(final_consonant) = syllable.final_consonant {
+            out.push_str(final_sound(final_consonant));
+        }
+    }
+    out
+}
+
+// Sound change between a syllable's final consonant and the next syllable's initial
+fn assimilate(final_consonant: char, initial: char, vowel: usize) -> (Option<char>, char) {
+    let (first, second) = split_final(final_consonant);
+
+    // Final consonant carries over onto a vowel-initial syllable
+    if initial == 'ㅇ' {
+        if final_consonant == 'ㅇ' {
+            return (Some('ㅇ'), initial);
+        }
+        // A silent ㅎ drops out, leaving what came before it to carry over
+        let (first, second) = match (first, second) {
+            (None, 'ㅎ') => return (None, 'ㅇ'),
+            (Some(rest), 'ㅎ') => (None, rest),
+            split => split,
+        };
+        let carried = match (second, vowel) {
+            ('ㄷ', VOWEL_I) => 'ㅈ',
+            ('ㅌ', VOWEL_I) => 'ㅊ',
+            (c, _) => c,
+        };
+        return (first, carried);
+    }
+
+    // ㅎ aspirates a following plain consonant
+    if second == 'ㅎ' {
+        return match initial {
+            'ㄱ' => (first, 'ㅋ'),
+            'ㄷ' => (first, 'ㅌ'),
+            'ㅈ' => (first, 'ㅊ'),
+            'ㅅ' => (first, 'ㅆ'),
+            'ㄴ' if first == Some('ㄹ') => (first, 'ㄹ'),
+            'ㄴ' => (Some('ㄴ'), 'ㄴ'),
+            _ => (first, initial),
+        };
+    }
+
+    let coda = coda(final_consonant);
+
+    // A stop before ㅎ becomes aspirated
+    if initial == 'ㅎ' {
+        return match coda {
+            'ㄱ' => (first.filter(|c| *c == 'ㄹ'), 'ㅋ'),
+            'ㄷ' if matches!(second, 'ㅈ' | 'ㅊ') => (None, 'ㅊ'),
+            'ㄷ' => (None, if vowel == VOWEL_I { 'ㅊ' } else { 'ㅌ' }),
+            'ㅂ' => (first.filter(|c| *c == 'ㄹ'), 'ㅍ'),
+            _ => (Some(coda), initial),
+        };
+    }
+
+    match (coda, initial) {
+        // Lateralization
+        ('ㄴ', 'ㄹ') | ('ㄹ', 'ㄴ') => (Some('ㄹ'), 'ㄹ'),
+        // ㄹ becomes ㄴ after nasals and stops, which then nasalize
+        ('ㅁ' | 'ㅇ', 'ㄹ') => (Some(coda), 'ㄴ'),
+        (_, 'ㄹ') if coda != 'ㄹ' => (Some(nasalize(coda)), 'ㄴ'),
+        // Stops nasalize before nasals
+        (_, 'ㄴ' | 'ㅁ') => (Some(nasalize(coda)), initial),
+        _ => (Some(coda), initial),
+    }
+}
+
+// Double finals split into (kept, carried); single finals carry over whole
+fn split_final(final_consonant: char) -> (Option<char>, char) {
+    match final_consonant {
+        'ㄳ' => (Some('ㄱ'), 'ㅅ'),
+        'ㄵ' => (Some('ㄴ'), 'ㅈ'),
+        'ㄶ' => (Some('ㄴ'), 'ㅎ'),
+        'ㄺ' => (Some('ㄹ'), 'ㄱ'),
+        'ㄻ' => (Some('ㄹ'), 'ㅁ'),
+        'ㄼ' => (Some('ㄹ'), 'ㅂ'),
+        'ㄽ' => (Some('ㄹ'), 'ㅅ'),
+        'ㄾ' => (Some('ㄹ'), 'ㅌ'),
+        'ㄿ' => (Some('ㄹ'), 'ㅍ'),
+        'ㅀ' => (Some('ㄹ'), 'ㅎ'),
+        'ㅄ' => (Some('ㅂ'), 'ㅅ'),
+        c => (None, c),
+    }
+}
+
+// The seven sounds a final consonant is actually pronounced as
+fn coda(final_consonant: char) -> char {
+    match final_consonant {
+        'ㄱ' | 'ㄲ' | 'ㅋ' | 'ㄳ' | 'ㄺ' => 'ㄱ',
+        'ㄴ' | 'ㄵ' | 'ㄶ' => 'ㄴ',
+        'ㄹ' | 'ㄼ' | 'ㄽ' | 'ㄾ' | 'ㅀ' => 'ㄹ',
+        'ㅁ' | 'ㄻ' => 'ㅁ',
+        'ㅂ' | 'ㅍ' | 'ㅄ' | 'ㄿ' => 'ㅂ',
+        'ㅇ' => 'ㅇ',
+        _ => 'ㄷ',
+    }
+}
+
+fn nasalize(coda: char) -> char {
+    match coda {
+        'ㄱ' => 'ㅇ',
+        'ㄷ' => 'ㄴ',
+        'ㅂ' => 'ㅁ',
+        c => c,
+    }
+}
+
+fn initial_sound(initial: char, after_l: bool) -> &'static str {
+    match initial {
+        'ㄱ' => "g",
+        'ㄲ' => "kk",
+        'ㄴ' => "n",
+        'ㄷ' => "d",
+        'ㄸ' => "tt",
+        'ㄹ' if after_l => "l",
+        'ㄹ' => "r",
+        'ㅁ' => "m",
+        'ㅂ' => "b",
+        'ㅃ' => "pp",
+        'ㅅ' => "s",
+        'ㅆ' => "ss",
+        'ㅈ' => "j",
+        'ㅉ' => "jj",
+        'ㅊ' => "ch",
+        'ㅋ' => "k",
+        'ㅌ' => "t",
+        'ㅍ' => "p",
+        'ㅎ' => "h",
+        _ => "",
+    }
+}
+
+fn final_sound(final_consonant: char) -> &'static str {
+    match coda(final_consonant) {
+        'ㄱ' => "k",
+        'ㄴ' => "n",
+        'ㄹ' => "l",
+        'ㅁ' => "m",
+        'ㅂ' => "p",
+        'ㅇ' => "ng",
+        _ => "t",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::romanize;
+
+    #[test]
+    fn sound_changes() {
+        for (hangul, romanized) in [
+            ("한국어", "hangugeo"), // Liaison
+            ("값이", "gapsi"), // Liaison of a double final
+            ("국립", "gungnip"), // Nasalization
+            ("국물", "gungmul"),
+            ("신라", "silla"), // ㄹ-assimilation
+            ("설날", "seollal"),
+            ("좋다", "jota"), // ㅎ aspiration
+            ("축하", "chuka"),
+            ("않아", "ana"), // Silent ㅎ
+            ("같이", "gachi"), // Palatalization
+            ("굳이", "guji"),
+        ] {
+            assert_eq!(romanize(hangul), romanized, "{}", hangul);
+        }
+    }
+
+    #[test]
+    fn balp_keeps_its_b() {
+        assert_eq!(romanize("밟다"), "bapda");
+        assert_eq!(romanize("밟는"), "bamneun");
+        assert_eq!(romanize("밟아"), "balba");
+        assert_eq!(romanize("넓다"), "neolda");
+    }
+}
@@ -0,0 +1,289 @@
+// Modified Hepburn romaji from hiragana and katakana: yōon (きゃ kya),
+// sokuon (っ doubles the next consonant), long vowels (ー, おう, うう as
+// macrons) and katakana extensions (ファ fa, ティ ti). Kanji have no reading
+// of their own, so lines with kanji are only romanized where a reading is
+// written inline as 漢字(かんじ) or {漢字|かんじ}.
+
+use crate::ruby;
+use crate::RubySegment;
+
+pub fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | '\u{30A1}'..='\u{30FA}' | 'ー')
+}
+
+pub fn is_kanji(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}' | '々' | '〆')
+}
+
+pub fn contains_kana(text: &str) -> bool {
+    text.chars().any(|c| is_kana(c) && c != 'ー')
+}
+
+// Romaji for a line, or None when it has kanji without an inline reading
+pub fn romanize(text: &str) -> Option<String> {
+    let (text, verb_endings) = match ruby::parse(text) {
+        Some(segments) => (ruby::spoken_text(&segments), verb_endings(&segments)),
+        None => (text.to_string(), Vec::new()),
+    };
+    if text.chars().any(is_kanji) || !contains_kana(&text) {
+        return None;
+    }
+    Some(kana_to_romaji(&text, &verb_endings))
+}
+
+// Positions in the spoken text of an う written right after a kanji with a reading,
+// as in {思|おも}う: a verb ending, romanized separately instead of as a long vowel
+fn verb_endings(segments: &[RubySegment]) -> Vec<usize> {
+    let mut endings = Vec::new();
+    let mut index = 0;
+    for (i, segment) in segments.iter().enumerate() {
+        let after_reading = i > 0 && segments[i - 1].reading.is_some();
+        if after_reading && segment.reading.is_none() && segment.text.starts_with('う') {
+            endings.push(index);
+        }
+        index += segment.reading.as_deref().unwrap_or(&segment.text).chars().count();
+    }
+    endings
+}
+
+fn kana_to_romaji(text: &str, verb_endings: &[usize]) -> String {
+    let chars: Vec<char> = text.chars().map(to_hiragana).collect();
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut sokuon = false;
+    let mut after_kana = false; // Previous output came from kana, so long vowels can merge into it
+    let mut after_n = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+
+        if c == 'っ' {
+            sokuon = true;
+            continue;
+        }
+        if c == 'ー' {
+            if after_kana {
+                lengthen_last_vowel(&mut out);
+            }
+            continue;
+        }
+
+        let Some(base) = kana(c) else {
+            sokuon = false;
+            after_kana = false;
+            after_n = false;
+            match super::punctuation(c) {
+                Some(p) => out.push_str(p),
+                None => out.push(c),
+            }
+            continue;
+        };
+
+        let verb_ending = verb_endings.contains(&(i - 1));
+        let mut syllable = base.to_string();
+        if let Some(&small) = chars.get(i) {
+            if let Some(combined) = combine(base, small) {
+                syllable = combined;
+                i += 1;
+            }
+        }
+
+        // おう, おお and うう are long vowels, except the う of a verb like {思|おも}う
+        let long_u = syllable == "u" && !verb_ending && (out.ends_with('u') || out.ends_with('o'));
+        let long_o = syllable == "o" && c != 'を' && out.ends_with('o');
+        if after_kana && !sokuon && (long_u || long_o) {
+            lengthen_last_vowel(&mut out);
+            after_n = false;
+            continue;
+        }
+
+        if after_n && syllable.starts_with(['a', 'i', 'u', 'e', 'o', 'y']) {
+            out.push('\'');
+        }
+        if sokuon {
+            if let Some(first) = syllable.chars().next().filter(|c| !"aiueon".contains(*c)) {
+                out.push(if syllable.starts_with("ch") { 't' } else { first });
+            }
+            sokuon = false;
+        }
+
+        out.push_str(&syllable);
+        after_kana = true;
+        after_n = c == 'ん';
+        // The particle を ends a word; nothing lengthens across it
+        if c == 'を' {
+            after_kana = false;
+        }
+    }
+    out
+}
+
+fn to_hiragana(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn lengthen_last_vowel(out: &mut String) {
+    let long = match out.chars().last() {
+        Some('a') => 'ā',
+        Some('i') => 'ī',
+        Some('u') => 'ū',
+        Some('e') => 'ē',
+        Some('o') => 'ō',
+        _ => return,
+    };
+    out.pop();
+    out.push(long);
+}
+
+// Kana followed by a small ゃゅょ or ぁぃぅぇぉ
+fn combine(base: &str, small: char) -> Option<String> {
+    let vowel = match small {
+        'ゃ' | 'ぁ' => "a",
+        'ぃ' => "i",
+        'ゅ' | 'ぅ' => "u",
+        'ぇ' => "e",
+        'ょ' | 'ぉ' => "o",
+        _ => return None,
+    };
+
+    if matches!(small, 'ゃ' | 'ゅ' | 'ょ') {
+        // Loanword sounds: デュ dyu, テュ tyu, フュ fyu, ヴュ vyu
+        let stem = match base {
+            "de" => "d",
+            "te" => "t",
+            "fu" => "f",
+            "vu" => "v",
+            _ => base.strip_suffix('i').filter(|s| !s.is_empty())?,
+        };
+        return Some(match stem {
+            "sh" | "ch" | "j" => format!("{}{}", stem, vowel),
+            _ => format!("{}y{}", stem, vowel),
+        });
+    }
+
+    let stem = match base {
+        "shi" => "sh",
+        "chi" => "ch",
+        "ji" => "j",
+        "fu" => "f",
+        "tsu" => "ts",
+        "te" | "to" => "t",
+        "de" | "do" => "d",
+        "u" => "w",
+        "vu" => "v",
+        _ => return None,
+    };
+    Some(format!("{}{}", stem, vowel))
+}
+
+fn kana(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::romanize;
+
+    #[test]
+    fn long_vowels() {
+        assert_eq!(romanize("もう").as_deref(), Some("mō"));
+        assert_eq!(romanize("きょう").as_deref(), Some("kyō"));
+        assert_eq!(romanize("がっこうに").as_deref(), Some("gakkōni"));
+        assert_eq!(romanize("いこう").as_deref(), Some("ikō"));
+        assert_eq!(romanize("しよう").as_deref(), Some("shiyō"));
+        assert_eq!(romanize("おはよう").as_deref(), Some("ohayō"));
+        assert_eq!(romanize("ありがとう").as_deref(), Some("arigatō"));
+        assert_eq!(romanize("こうこう").as_deref(), Some("kōkō"));
+        assert_eq!(romanize("{行|い}こう").as_deref(), Some("ikō"));
+        assert_eq!(romanize("きみをおもう、").as_deref(), Some("kimioomō,"));
+    }
+
+    #[test]
+    fn verb_ending_u_after_a_reading_stays_separate() {
+        assert_eq!(romanize("{思|おも}う").as_deref(), Some("omou"));
+        assert_eq!(romanize("君(きみ)を思(おも)う").as_deref(), Some("kimioomou"));
+        assert_eq!(romanize("{歌|うた}うよ").as_deref(), Some("utauyo"));
+    }
+
+    #[test]
+    fn small_ya_yu_yo_after_e_and_u_row() {
+        assert_eq!(romanize("デュエット").as_deref(), Some("dyuetto"));
+        assert_eq!(romanize("テューバ").as_deref(), Some("tyūba"));
+        assert_eq!(romanize("フュージョン").as_deref(), Some("fyūjon"));
+    }
+}
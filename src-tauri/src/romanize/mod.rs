@@ -0,0 +1,82 @@
+pub mod hangul;
+pub mod kana;
+pub mod pinyin;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config, ruby, LyricsData};
+
+const ROMANIZE_CONFIG_FILE: &str = "romanize.json";
+
+// Which scripts get a generated pron_text when the sender left it empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RomanizeConfig {
+    pub hangul: bool,   // Korean -> Revised Romanization
+    pub japanese: bool, // Japanese kana -> Hepburn romaji
+    pub chinese: bool,  // Chinese characters -> tone-marked pinyin
+}
+
+impl Default for RomanizeConfig {
+    fn default() -> Self {
+        Self {
+            hangul: true,
+            japanese: true,
+            chinese: true,
+        }
+    }
+}
+
+pub fn load_romanize_config() -> RomanizeConfig {
+    config::load_json(ROMANIZE_CONFIG_FILE)
+}
+
+pub fn save_romanize_config(romanize: &RomanizeConfig) -> Result<(), String> {
+    config::save_json(ROMANIZE_CONFIG_FILE, romanize)
+}
+
+// Fill missing pron_text from the line text; sender-provided pronunciations are kept
+pub fn fill_pronunciation(lyrics_data: &mut LyricsData, romanize: &RomanizeConfig) {
+    // Kanji-only lines in a Japanese song are not Chinese
+    let is_japanese = lyrics_data.lyrics.iter().any(|line| kana::contains_kana(&line.text));
+
+    for line in &mut lyrics_data.lyrics {
+        if line.pron_text.as_deref().is_some_and(|p| !p.trim().is_empty()) {
+            continue;
+        }
+        // Ruby readings stand in for the characters they annotate
+        let text = match line.ruby.as_deref() {
+            Some(segments) => ruby::spoken_text(segments),
+            None => line.text.clone(),
+        };
+        if romanize.hangul && hangul::contains_hangul(&text) {
+            line.pron_text = Some(hangul::romanize(&text));
+        } else if romanize.japanese && kana::contains_kana(&text) {
+            // None for kanji without a reading; leave the row empty rather than guess
+            if let Some(romaji) = kana::romanize(&text) {
+                line.pron_text = Some(romaji);
+            }
+        } else if romanize.chinese && !is_japanese && pinyin::contains_han(&line.text) {
+            line.pron_text = Some(pinyin::romanize(&text));
+        }
+    }
+}
+
+// Full-width punctuation in romanized text
+fn punctuation(c: char) -> Option<&'static str> {
+    Some(match c {
+        '、' | '，' => ",",
+        '。' | '．' => ".",
+        '！' => "!",
+        '？' => "?",
+        '；' => ";",
+        '：' => ":",
+        '「' | '」' | '『' | '』' => "\"",
+        '（' => "(",
+        '）' => ")",
+        '～' | '〜' => "~",
+        '・' => " ",
+        '\u{3000}' => " ",
+        _ => return None,
+    })
+}
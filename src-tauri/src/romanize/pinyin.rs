@@ -0,0 +1,122 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::chinese_script::PhraseTable;
+
+// Tone-marked pinyin from an embedded per-character table. Each character
+// gets its most common Mandarin reading, unless it is part of a word in the
+// phrase table (银行 yín háng); syllables are space separated.
+
+const PINYIN_TABLE: &str = include_str!("pinyin.txt");
+const PINYIN_PHRASES: &str = include_str!("pinyin_phrases.txt");
+
+fn table() -> &'static HashMap<char, &'static str> {
+    static TABLE: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = HashMap::new();
+        for line in PINYIN_TABLE.lines().filter(|l| !l.starts_with('#')) {
+            if let Some((syllable, chars)) = line.split_once(' ') {
+                for c in chars.chars() {
+                    table.insert(c, syllable);
+                }
+            }
+        }
+        table
+    })
+}
+
+fn phrases() -> &'static PhraseTable {
+    static PHRASES: OnceLock<PhraseTable> = OnceLock::new();
+    PHRASES.get_or_init(|| PhraseTable::parse(PINYIN_PHRASES))
+}
+
+// Every syllable in the table without its tone, e.g. "hao", "lü"
+fn syllables() -> &'static HashSet<String> {
+    static SYLLABLES: OnceLock<HashSet<String>> = OnceLock::new();
+    SYLLABLES.get_or_init(|| table().values().map(|syllable| toneless(syllable)).collect())
+}
+
+// "hǎo" or "hao3" -> "hao"; "lv" -> "lü"
+fn toneless(syllable: &str) -> String {
+    syllable
+        .trim_end_matches(['1', '2', '3', '4', '5'])
+        .chars()
+        .map(|c| match c {
+            'ā' | 'á' | 'ǎ' | 'à' => 'a',
+            'ē' | 'é' | 'ě' | 'è' => 'e',
+            'ī' | 'í' | 'ǐ' | 'ì' => 'i',
+            'ō' | 'ó' | 'ǒ' | 'ò' => 'o',
+            'ū' | 'ú' | 'ǔ' | 'ù' => 'u',
+            'ǖ' | 'ǘ' | 'ǚ' | 'ǜ' | 'v' => 'ü',
+            _ => c,
+        })
+        .collect()
+}
+
+// A single pinyin syllable, with tone marks, a tone number or no tone
+pub fn is_syllable(word: &str) -> bool {
+    syllables().contains(&toneless(&word.to_lowercase()))
+}
+
+// A tone mark or tone digit: "hǎo", "hao3"
+pub fn has_tone(word: &str) -> bool {
+    word.ends_with(['1', '2', '3', '4', '5']) || toneless(word) != word.replace('v', "ü")
+}
+
+pub fn is_han(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+pub fn contains_han(text: &str) -> bool {
+    text.chars().any(is_han)
+}
+
+pub fn romanize(text: &str) -> String {
+    let table = table();
+    let phrases = phrases();
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut after_syllable = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let reading = phrases
+            .longest(&chars, i)
+            .or_else(|| table.get(&c).map(|&syllable| (1, syllable)));
+        if let Some((len, syllables)) = reading {
+            if !out.is_empty() && !out.ends_with(char::is_whitespace) {
+                out.push(' ');
+            }
+            out.push_str(syllables);
+            after_syllable = true;
+            i += len;
+            continue;
+        }
+        i += 1;
+
+        if after_syllable && c.is_alphanumeric() {
+            out.push(' ');
+        }
+        match super::punctuation(c) {
+            Some(p) => out.push_str(p),
+            None => out.push(c),
+        }
+        after_syllable = false;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::romanize;
+
+    #[test]
+    fn phrase_readings_win_over_single_characters() {
+        assert_eq!(romanize("银行"), "yín háng");
+        assert_eq!(romanize("銀行"), "yín háng");
+        assert_eq!(romanize("我去银行"), "wǒ qù yín háng");
+        assert_eq!(romanize("行走"), "xíng zǒu");
+        assert_eq!(romanize("长大的头发"), "zhǎng dà de tóu fa");
+    }
+}
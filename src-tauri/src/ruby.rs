@@ -0,0 +1,188 @@
+use crate::romanize::kana::{is_kana, is_kanji};
+use crate::romanize::pinyin;
+use crate::{LyricsData, RubySegment};
+
+// Inline readings in lyric text: {base|reading} for any base, and a parenthesized
+// reading right after a run of kanji/hanzi, either in kana, 漢字(かんじ) or
+// 漢字（かんじ）, or in pinyin with one valid syllable per character and tones,
+// 汉字(hàn zì) or 汉字(han4 zi4)
+
+// Segments of a text with inline readings, or None when it has none
+pub fn parse(text: &str) -> Option<Vec<RubySegment>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut segments: Vec<RubySegment> = Vec::new();
+    let mut plain = String::new();
+    let mut annotated = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '{' {
+            if let Some((base, reading, next)) = braced_after(&chars, i) {
+                push_plain(&mut segments, &mut plain);
+                segments.push(RubySegment {
+                    text: base,
+                    reading: Some(reading),
+                });
+                annotated = true;
+                i = next;
+                continue;
+            }
+        }
+
+        if is_kanji(c) {
+            let end = chars[i..].iter().position(|&c| !is_kanji(c)).map_or(chars.len(), |p| i + p);
+            if let Some((reading, next)) = reading_after(&chars, end, end - i) {
+                push_plain(&mut segments, &mut plain);
+                segments.push(RubySegment {
+                    text: chars[i..end].iter().collect(),
+                    reading: Some(reading),
+                });
+                annotated = true;
+                i = next;
+            } else {
+                plain.extend(&chars[i..end]);
+                i = end;
+            }
+            continue;
+        }
+
+        plain.push(c);
+        i += 1;
+    }
+
+    push_plain(&mut segments, &mut plain);
+    annotated.then_some(segments)
+}
+
+fn push_plain(segments: &mut Vec<RubySegment>, plain: &mut String) {
+    if !plain.is_empty() {
+        segments.push(RubySegment {
+            text: std::mem::take(plain),
+            reading: None,
+        });
+    }
+}
+
+// {base|reading} starting at `start`: base, reading and the index after it
+fn braced_after(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let close = chars[start + 1..].iter().position(|&c| c == '}' || c == '{')? + start + 1;
+    if chars[close] != '}' {
+        return None;
+    }
+    let inner: String = chars[start + 1..close].iter().collect();
+    let (base, reading) = inner.split_once('|')?;
+    if base.trim().is_empty() || reading.trim().is_empty() {
+        return None;
+    }
+    Some((base.to_string(), reading.trim().to_string(), close + 1))
+}
+
+// A parenthesized reading for `count` characters starting at `start`, and the index after it
+fn reading_after(chars: &[char], start: usize, count: usize) -> Option<(String, usize)> {
+    let close = match chars.get(start)? {
+        '(' => ')',
+        '（' => '）',
+        _ => return None,
+    };
+    let end = chars[start + 1..].iter().position(|&c| c == close)? + start + 1;
+    let reading: String = chars[start + 1..end].iter().collect();
+    let reading = reading.trim();
+    if reading.is_empty() {
+        return None;
+    }
+
+    let kana = reading.chars().all(is_kana);
+    // Only real syllables, one per character, with tones (a neutral one may go unmarked),
+    // so backing vocals like 爱你(oh yeah) and asides like 我(wo) stay in the text
+    let syllables: Vec<&str> = reading.split([' ', '\'']).filter(|s| !s.is_empty()).collect();
+    let pinyin = syllables.len() == count
+        && syllables.iter().all(|syllable| pinyin::is_syllable(syllable))
+        && syllables.iter().any(|syllable| pinyin::has_tone(syllable));
+    (kana || pinyin).then(|| (reading.to_string(), end + 1))
+}
+
+// The text as displayed: segments without their readings
+pub fn base_text(segments: &[RubySegment]) -> String {
+    segments.iter().map(|segment| segment.text.as_str()).collect()
+}
+
+// The text as sung: readings in place of their base, for romanizing
+pub fn spoken_text(segments: &[RubySegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.reading.as_deref().unwrap_or(&segment.text))
+        .collect()
+}
+
+// Remove inline readings, keeping the base text
+pub fn strip(text: &str) -> String {
+    match parse(text) {
+        Some(segments) => base_text(&segments),
+        None => text.to_string(),
+    }
+}
+
+// Move inline readings out of line text into ruby segments. Word and background
+// texts lose their readings too, so they still add up to the line text.
+pub fn apply_ruby(lyrics_data: &mut LyricsData) {
+    for line in &mut lyrics_data.lyrics {
+        match parse(&line.text) {
+            Some(segments) => {
+                line.text = base_text(&segments);
+                line.ruby = Some(segments);
+            }
+            // Sender-provided segments must match the text they annotate
+            None => {
+                if line.ruby.as_deref().is_some_and(|segments| base_text(segments) != line.text) {
+                    line.ruby = None;
+                }
+            }
+        }
+
+        if let Some(background) = line.background.as_mut() {
+            background.text = strip(&background.text);
+        }
+        let background_words = line.background.as_mut().and_then(|bg| bg.words.as_mut());
+        for word in line.words.iter_mut().chain(background_words).flatten() {
+            word.text = strip(&word.text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, reading: Option<&str>) -> RubySegment {
+        RubySegment {
+            text: text.to_string(),
+            reading: reading.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn pinyin_readings() {
+        assert_eq!(parse("我爱你（wǒ ài nǐ）"), Some(vec![segment("我爱你", Some("wǒ ài nǐ"))]));
+        assert_eq!(parse("爱你(ai4 ni3)"), Some(vec![segment("爱你", Some("ai4 ni3"))]));
+    }
+
+    #[test]
+    fn latin_backing_vocals_stay_in_the_text() {
+        assert_eq!(parse("爱(oh)"), None);
+        assert_eq!(parse("爱你(oh yeah)"), None);
+        assert_eq!(parse("我爱你(Chorus)"), None);
+        assert_eq!(strip("爱你(oh yeah)"), "爱你(oh yeah)");
+    }
+
+    #[test]
+    fn toneless_latin_stays_in_the_text() {
+        assert_eq!(parse("我(wo)"), None);
+        assert_eq!(parse("行(xing)"), None);
+        assert_eq!(parse("我爱你(wo ai ni)"), None);
+        assert_eq!(parse("一起走(go go go)"), None);
+        assert_eq!(parse("我的(wǒ de)"), Some(vec![segment("我的", Some("wǒ de"))]));
+        assert_eq!(parse("绿(lv4)"), Some(vec![segment("绿", Some("lv4"))]));
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use crate::{LyricsData, Script};
+
+// Script of a single character; digits, punctuation, symbols and spaces have none
+pub fn char_script(c: char) -> Option<Script> {
+    Some(match c {
+        'A'..='Z' | 'a'..='z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => Script::Latin,
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Script::Greek,
+        '\u{0400}'..='\u{052F}' => Script::Cyrillic,
+        '\u{0530}'..='\u{058F}' => Script::Armenian,
+        '\u{10A0}'..='\u{10FF}' => Script::Georgian,
+        '\u{0590}'..='\u{05FF}' | '\u{FB1D}'..='\u{FB4F}' => Script::Hebrew,
+        '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' | '\u{08A0}'..='\u{08FF}' | '\u{FB50}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}' => Script::Arabic,
+        '\u{0900}'..='\u{097F}' => Script::Devanagari,
+        '\u{0980}'..='\u{09FF}' => Script::Bengali,
+        '\u{0B80}'..='\u{0BFF}' => Script::Tamil,
+        '\u{0E00}'..='\u{0E7F}' => Script::Thai,
+        '\u{0E80}'..='\u{0EFF}' => Script::Lao,
+        '\u{1780}'..='\u{17FF}' => Script::Khmer,
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => Script::Hangul,
+        '\u{3041}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9D}' => Script::Kana,
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}' | '々' => Script::Han,
+        _ => return None,
+    })
+}
+
+// Script with the most letters; any kana makes Han text Japanese
+pub fn detect(text: &str) -> Option<Script> {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for script in text.chars().filter_map(char_script) {
+        *counts.entry(script).or_default() += weight(script);
+    }
+    if counts.contains_key(&Script::Kana) {
+        let japanese = counts.remove(&Script::Kana).unwrap_or(0) + counts.remove(&Script::Han).unwrap_or(0);
+        counts.insert(Script::Kana, japanese);
+    }
+    dominant(counts)
+}
+
+// A Hangul, kana or Han character is a whole syllable, worth a few Latin letters
+fn weight(script: Script) -> usize {
+    match script {
+        Script::Hangul | Script::Kana | Script::Han => 3,
+        _ => 1,
+    }
+}
+
+// Tag every line, then the whole lyrics with the script most lines are in
+pub fn tag_lyrics(lyrics_data: &mut LyricsData) {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for line in &mut lyrics_data.lyrics {
+        line.script = detect(&line.text);
+        if let Some(script) = line.script {
+            *counts.entry(script).or_default() += 1;
+        }
+    }
+
+    // Kanji-only lines of a Japanese song are Japanese too
+    let han_lines = counts.get(&Script::Han).copied().unwrap_or(0);
+    if counts.get(&Script::Kana).is_some_and(|&kana_lines| kana_lines >= han_lines) {
+        for line in &mut lyrics_data.lyrics {
+            if line.script == Some(Script::Han) {
+                line.script = Some(Script::Kana);
+            }
+        }
+        counts.remove(&Script::Han);
+        *counts.entry(Script::Kana).or_default() += han_lines;
+    }
+
+    lyrics_data.script = dominant(counts);
+}
+
+// Ties go to the script that sorts first, so the result is stable
+fn dominant(counts: HashMap<Script, usize>) -> Option<Script> {
+    counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then((*b as u8).cmp(&(*a as u8))))
+        .map(|(script, _)| script)
+}
@@ -0,0 +1,67 @@
+use sha2::{Digest, Sha256};
+
+use crate::TrackInfo;
+
+// Case- and punctuation-insensitive form of a title/artist for matching
+pub fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// First credited artist of "A, B", "A & B", "A feat. B" or "A x B"
+pub fn primary_artist(artist: &str) -> &str {
+    let lower = artist.to_ascii_lowercase();
+    let cut = [",", "&", ";", " feat", " ft.", " x ", " with "]
+        .iter()
+        .filter_map(|sep| lower.find(sep))
+        .min()
+        .unwrap_or(artist.len());
+    artist.get(..cut).unwrap_or(artist).trim()
+}
+
+// Identity of a track across plays, independent of formatting differences
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrackKey {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub duration_secs: u64, // Rounded so millisecond jitter between senders still matches
+}
+
+impl TrackKey {
+    pub fn from_track(track: &TrackInfo) -> Self {
+        Self {
+            title: normalize(&track.title),
+            artist: normalize(&track.artist),
+            album: normalize(&track.album),
+            duration_secs: (track.duration + 500) / 1000,
+        }
+    }
+
+    // Title, artist and duration only: the same recording shows up on albums,
+    // singles and compilations, and its timing is the same on all of them
+    pub fn identity(track: &TrackInfo) -> Self {
+        Self {
+            album: String::new(),
+            ..Self::from_track(track)
+        }
+    }
+
+    // Stable hex digest, usable as a file name or map key
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [&self.title, &self.artist, &self.album] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        hasher.update(self.duration_secs.to_le_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .take(16)
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
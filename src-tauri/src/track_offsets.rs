@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::track_key::TrackKey;
+use crate::{config, LyricLine, TrackInfo};
+
+const TRACK_OFFSETS_FILE: &str = "track_offsets.json";
+
+// Per-track lyric timing offsets in ms (positive shows lyrics later), keyed by track identity
+pub struct TrackOffsets {
+    offsets: Mutex<HashMap<String, i64>>,
+}
+
+impl TrackOffsets {
+    pub fn load() -> Self {
+        Self {
+            offsets: Mutex::new(config::load_json(TRACK_OFFSETS_FILE)),
+        }
+    }
+
+    pub fn get(&self, track: &TrackInfo) -> i64 {
+        let digest = TrackKey::identity(track).digest();
+        self.offsets
+            .lock()
+            .ok()
+            .and_then(|offsets| offsets.get(&digest).copied())
+            .unwrap_or(0)
+    }
+
+    // Add `step` to the track's offset and persist it; returns the new offset
+    pub fn adjust(&self, track: &TrackInfo, step: i64) -> Result<i64, String> {
+        let digest = TrackKey::identity(track).digest();
+        let mut offsets = self.offsets.lock().map_err(|e| e.to_string())?;
+        let offset = offsets.get(&digest).copied().unwrap_or(0) + step;
+        if offset == 0 {
+            offsets.remove(&digest);
+        } else {
+            offsets.insert(digest, offset);
+        }
+        config::save_json(TRACK_OFFSETS_FILE, &*offsets)?;
+        Ok(offset)
+    }
+}
+
+// Move every timestamp of the lines by `delta` ms
+pub fn shift_lyrics(lyrics: &mut [LyricLine], delta: i64) {
+    for line in lyrics {
+        line.start_time += delta;
+        line.end_time = line.end_time.map(|t| t + delta);
+
+        let background_words = line.background.as_mut().and_then(|bg| bg.words.as_mut());
+        for word in line.words.iter_mut().chain(background_words).flatten() {
+            word.start_time += delta;
+            word.end_time = word.end_time.map(|t| t + delta);
+        }
+    }
+}
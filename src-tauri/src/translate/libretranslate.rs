@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::{TranslateFuture, Translator};
+
+// Self-hosted LibreTranslate (or an API-compatible server), e.g. http://127.0.0.1:5000
+pub struct LibreTranslate {
+    http: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a [String],
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: TranslatedText,
+}
+
+// A batch request answers with a list; some servers answer a one-item batch with a plain string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TranslatedText {
+    Batch(Vec<String>),
+    Single(String),
+}
+
+impl LibreTranslate {
+    pub fn new(endpoint: &str, api_key: &str) -> Self {
+        let api_key = api_key.trim();
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(20))
+                .build()
+                .unwrap_or_default(),
+            endpoint: endpoint.trim().trim_end_matches('/').to_string(),
+            api_key: (!api_key.is_empty()).then(|| api_key.to_string()),
+        }
+    }
+}
+
+impl Translator for LibreTranslate {
+    fn translate<'a>(&'a self, texts: &'a [String], target: &'a str) -> TranslateFuture<'a> {
+        Box::pin(async move {
+            let request = TranslateRequest {
+                q: texts,
+                source: "auto",
+                target,
+                format: "text",
+                api_key: self.api_key.as_deref(),
+            };
+            let response = self
+                .http
+                .post(format!("{}/translate", self.endpoint))
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| format!("Translation request failed: {}", e))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!("Translation server returned {}: {}", status, body.trim()));
+            }
+
+            let translated = match response
+                .json::<TranslateResponse>()
+                .await
+                .map_err(|e| format!("Invalid translation response: {}", e))?
+                .translated_text
+            {
+                TranslatedText::Batch(texts) => texts,
+                TranslatedText::Single(text) => vec![text],
+            };
+            if translated.len() != texts.len() {
+                return Err(format!(
+                    "Translation server returned {} results for {} lines",
+                    translated.len(),
+                    texts.len()
+                ));
+            }
+            Ok(translated)
+        })
+    }
+}
@@ -0,0 +1,268 @@
+pub mod libretranslate;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::{bidi, config, LyricsData, TextDirection};
+
+const TRANSLATE_CONFIG_FILE: &str = "translate.json";
+const CACHE_FILE: &str = "translation_cache.json";
+const MAX_CACHE_ENTRIES: usize = 50_000;
+const BATCH_SIZE: usize = 50;
+// New translations are written to disk at most this often, and on exit
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+pub type TranslateFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + Send + 'a>>;
+
+// A machine translation backend for lyric lines
+pub trait Translator: Send + Sync {
+    // Translate each text into `target` (e.g. "en"), returning results in the same order
+    fn translate<'a>(&'a self, texts: &'a [String], target: &'a str) -> TranslateFuture<'a>;
+}
+
+// Filling in missing trans_text with machine translation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranslateConfig {
+    pub enabled: bool,
+    pub endpoint: String,        // LibreTranslate-compatible server, e.g. http://127.0.0.1:5000
+    pub api_key: String,         // Only if the server requires one
+    pub target_language: String, // ISO 639-1 code, e.g. "en"
+}
+
+impl Default for TranslateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://127.0.0.1:5000".to_string(),
+            api_key: String::new(),
+            target_language: "en".to_string(),
+        }
+    }
+}
+
+pub fn load_translate_config() -> TranslateConfig {
+    config::load_json(TRANSLATE_CONFIG_FILE)
+}
+
+pub fn save_translate_config(translate: &TranslateConfig) -> Result<(), String> {
+    let endpoint = translate.endpoint.trim();
+    if translate.enabled && !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+        return Err(format!("Invalid translation server URL: {}", translate.endpoint));
+    }
+    if translate.enabled && translate.target_language.trim().is_empty() {
+        return Err("Target language is required".to_string());
+    }
+    config::save_json(TRANSLATE_CONFIG_FILE, translate)
+}
+
+// Machine translation that arrived after the lyrics were shown
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationsEvent {
+    pub target_language: String,
+    pub lines: Vec<LineTranslation>,
+    pub profanity_masked: bool, // The track's flag after these lines, which may have been masked
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineTranslation {
+    pub line_index: usize,
+    pub trans_text: String,
+    pub trans_direction: Option<TextDirection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedTranslation {
+    text: String,
+    used: u64, // Last-use sequence number, for evicting the least recently used
+}
+
+// Lines waiting for translation: (line index, source text)
+pub struct TranslationJob {
+    translator: Arc<dyn Translator>,
+    pub target_language: String,
+    pub lines: Vec<(usize, String)>,
+}
+
+pub struct Translation {
+    config: Mutex<TranslateConfig>,
+    translator: Mutex<Option<Arc<dyn Translator>>>,
+    cache: Mutex<HashMap<String, CachedTranslation>>, // Keyed by target language + source text
+    dirty: AtomicBool,                                 // Cache changed since it was last saved
+}
+
+impl Translation {
+    pub fn load() -> Self {
+        let config = load_translate_config();
+        Self {
+            translator: Mutex::new(build_translator(&config)),
+            config: Mutex::new(config),
+            cache: Mutex::new(config::load_json(CACHE_FILE)),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn config(&self) -> TranslateConfig {
+        self.config.lock().map(|config| config.clone()).unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: TranslateConfig) -> Result<(), String> {
+        save_translate_config(&config)?;
+        *self.translator.lock().map_err(|e| e.to_string())? = build_translator(&config);
+        *self.config.lock().map_err(|e| e.to_string())? = config;
+        Ok(())
+    }
+
+    pub fn clear_cache(&self) -> Result<(), String> {
+        let mut cache = self.cache.lock().map_err(|e| e.to_string())?;
+        cache.clear();
+        self.dirty.store(false, Ordering::SeqCst);
+        config::save_json(CACHE_FILE, &*cache)
+    }
+
+    // Save the cache if translations were added since the last save
+    pub fn flush(&self) {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let Ok(cache) = self.cache.lock() else {
+            return;
+        };
+        if let Err(e) = config::save_json(CACHE_FILE, &*cache) {
+            eprintln!("Failed to save translation cache: {}", e);
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    // Fill trans_text from earlier translations, so repeat plays need no request
+    pub fn apply_cached(&self, lyrics_data: &mut LyricsData) {
+        let config = self.config();
+        if !config.enabled {
+            return;
+        }
+        let Ok(mut cache) = self.cache.lock() else {
+            return;
+        };
+        let used = next_sequence(&cache);
+        for line in &mut lyrics_data.lyrics {
+            if line.trans_text.is_some() {
+                continue;
+            }
+            let key = cache_key(&config.target_language, &bidi::strip_isolates(&line.text));
+            if let Some(cached) = cache.get_mut(&key) {
+                cached.used = used;
+                line.trans_text = useful_translation(&line.text, &cached.text);
+            }
+        }
+    }
+
+    // Lines that still have no translation, from lyrics processed up to (not including) masking
+    pub fn missing(&self, lyrics_data: &LyricsData) -> Option<TranslationJob> {
+        let config = self.config();
+        if !config.enabled {
+            return None;
+        }
+        let translator = self.translator.lock().ok()?.clone()?;
+        let cache = self.cache.lock().ok()?;
+
+        let lines: Vec<(usize, String)> = lyrics_data
+            .lyrics
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.trans_text.is_none())
+            .map(|(index, line)| (index, bidi::strip_isolates(&line.text)))
+            .filter(|(_, text)| text.chars().any(char::is_alphabetic))
+            .filter(|(_, text)| !cache.contains_key(&cache_key(&config.target_language, text)))
+            .collect();
+
+        (!lines.is_empty()).then(|| TranslationJob {
+            translator,
+            target_language: config.target_language,
+            lines,
+        })
+    }
+
+    // Translate the job's lines in batches and cache the results; returns
+    // (line index, source text, translation) for lines worth showing
+    pub async fn run(&self, job: &TranslationJob) -> Result<Vec<(usize, String, String)>, String> {
+        let mut unique: Vec<String> = job.lines.iter().map(|(_, text)| text.clone()).collect();
+        unique.sort();
+        unique.dedup();
+
+        let mut translated: HashMap<String, String> = HashMap::new();
+        for batch in unique.chunks(BATCH_SIZE) {
+            let results = job.translator.translate(batch, &job.target_language).await?;
+            translated.extend(batch.iter().cloned().zip(results));
+        }
+
+        self.store(&job.target_language, &translated);
+
+        Ok(job
+            .lines
+            .iter()
+            .filter_map(|(index, text)| {
+                let translation = useful_translation(text, translated.get(text)?)?;
+                Some((*index, text.clone(), translation))
+            })
+            .collect())
+    }
+
+    fn store(&self, target_language: &str, translated: &HashMap<String, String>) {
+        let Ok(mut cache) = self.cache.lock() else {
+            return;
+        };
+        let used = next_sequence(&cache);
+        for (text, translation) in translated {
+            cache.insert(
+                cache_key(target_language, text),
+                CachedTranslation {
+                    text: translation.clone(),
+                    used,
+                },
+            );
+        }
+
+        if cache.len() > MAX_CACHE_ENTRIES {
+            let mut used: Vec<u64> = cache.values().map(|entry| entry.used).collect();
+            used.sort_unstable();
+            let cutoff = used[cache.len() - MAX_CACHE_ENTRIES * 4 / 5];
+            cache.retain(|_, entry| entry.used >= cutoff);
+        }
+        // Written by flush, not on every job
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+}
+
+fn build_translator(config: &TranslateConfig) -> Option<Arc<dyn Translator>> {
+    if !config.enabled || config.endpoint.trim().is_empty() {
+        return None;
+    }
+    Some(Arc::new(libretranslate::LibreTranslate::new(&config.endpoint, &config.api_key)))
+}
+
+fn next_sequence(cache: &HashMap<String, CachedTranslation>) -> u64 {
+    cache.values().map(|entry| entry.used + 1).max().unwrap_or(0)
+}
+
+fn cache_key(target_language: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(target_language.trim().to_lowercase().as_bytes());
+    hasher.update([0]);
+    hasher.update(text.trim().as_bytes());
+    hasher.finalize()[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// A translation that only echoes the line (already in the target language) is not shown
+fn useful_translation(text: &str, translation: &str) -> Option<String> {
+    let translation = translation.trim();
+    let same = bidi::strip_isolates(text).trim().to_lowercase() == translation.to_lowercase();
+    (!translation.is_empty() && !same).then(|| translation.to_string())
+}
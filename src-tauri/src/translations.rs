@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{config, LyricsData};
+
+const TRANSLATIONS_CONFIG_FILE: &str = "translations.json";
+
+// Which of the sender's per-language translations to show
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranslationsConfig {
+    pub languages: Vec<String>, // Language codes, most preferred first; empty keeps every translation
+}
+
+pub fn load_translations_config() -> TranslationsConfig {
+    config::load_json(TRANSLATIONS_CONFIG_FILE)
+}
+
+// Codes are stored trimmed and lowercase, without duplicates
+pub fn save_translations_config(translations: &TranslationsConfig) -> Result<TranslationsConfig, String> {
+    let mut languages: Vec<String> = Vec::new();
+    for language in &translations.languages {
+        let language = language.trim().to_lowercase().replace('_', "-");
+        if language.is_empty() {
+            continue;
+        }
+        if !language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid language code: {}", language));
+        }
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+
+    let translations = TranslationsConfig { languages };
+    config::save_json(TRANSLATIONS_CONFIG_FILE, &translations)?;
+    Ok(translations)
+}
+
+// "en" matches "en", "EN" and "en-US"; "en-US" matches only itself
+fn matches_language(code: &str, preferred: &str) -> bool {
+    let code = code.trim().to_lowercase().replace('_', "-");
+    code == preferred || (!preferred.contains('-') && code.split('-').next() == Some(preferred))
+}
+
+// Keep the preferred translations of each line and show the most preferred one as
+// trans_text; a sender trans_text stays when none of the line's translations are wanted
+pub fn select_translations(lyrics_data: &mut LyricsData, translations: &TranslationsConfig) {
+    let mut shown: Vec<String> = Vec::new();
+
+    for line in &mut lyrics_data.lyrics {
+        let Some(available) = line.translations.take() else {
+            continue;
+        };
+        let available: BTreeMap<String, String> = available
+            .into_iter()
+            .filter(|(code, text)| !code.trim().is_empty() && !text.trim().is_empty())
+            .collect();
+
+        let selected: Vec<(String, String)> = if translations.languages.is_empty() {
+            available.into_iter().collect()
+        } else {
+            translations
+                .languages
+                .iter()
+                .filter_map(|preferred| {
+                    available
+                        .iter()
+                        .find(|(code, _)| matches_language(code, preferred))
+                        .map(|(code, text)| (code.clone(), text.clone()))
+                })
+                .collect()
+        };
+
+        let Some((_, first)) = selected.first() else {
+            continue;
+        };
+        // Without a preference list the sender's own trans_text is the one it meant to show
+        if !translations.languages.is_empty() || line.trans_text.is_none() {
+            line.trans_text = Some(first.clone());
+        }
+        for (code, _) in &selected {
+            if !shown.contains(code) {
+                shown.push(code.clone());
+            }
+        }
+        line.translations = Some(selected.into_iter().collect());
+    }
+
+    // Order languages by preference; without one, in code order
+    if translations.languages.is_empty() {
+        shown.sort();
+    } else {
+        shown.sort_by_key(|code| {
+            translations
+                .languages
+                .iter()
+                .position(|preferred| matches_language(code, preferred))
+        });
+    }
+    lyrics_data.translation_languages = (!shown.is_empty()).then_some(shown);
+}
@@ -26,6 +26,7 @@ const CURRENT_VERSION = __APP_VERSION__;
 import "./App.css";
 import type { TrackInfo, LyricLine, LyricsEvent, ProgressEvent } from "./types";
 import SettingsPanel from "./SettingsPanel";
+import { applyPreset, getPresetById } from "./presets";
 import SetupWizard from "./SetupWizard";
 
 // Default settings
@@ -603,6 +604,23 @@ function App() {
       setIsHovering(event.payload);
     });
 
+    // Listen for HTTP command API requests (main window applies, others follow via storage)
+    const unlistenPreset = listen<string>("apply-preset", (event) => {
+      const preset = getPresetById(event.payload);
+      if (preset && !isSettingsWindow) {
+        setSettings((prev) => applyPreset(prev, preset));
+      }
+    });
+
+    const unlistenDisplayToggles = listen<Partial<OverlaySettings>>(
+      "display-toggles",
+      (event) => {
+        if (!isSettingsWindow) {
+          setSettings((prev) => ({ ...prev, ...event.payload }));
+        }
+      }
+    );
+
     // Initial setup: Sync lock state with backend
     if (!isSettingsWindow) {
       // Default is locked - set both lock state and ignore cursor events
@@ -620,6 +638,8 @@ function App() {
       unlistenProgress.then((fn) => fn());
       unlistenLockUpdate.then((fn) => fn());
       unlistenHover.then((fn) => fn());
+      unlistenPreset.then((fn) => fn());
+      unlistenDisplayToggles.then((fn) => fn());
     };
   }, []); // Run once
 
@@ -4,6 +4,7 @@ export interface TrackInfo {
     album: string;
     albumArt?: string;
     duration: number;
+    profanityMasked?: boolean; // Explicit words in the lyrics were masked
 }
 
 export interface LyricLine {
@@ -12,13 +13,61 @@ export interface LyricLine {
     text: string;
     pronText?: string;
     transText?: string;
+    translations?: Record<string, string> | null; // Translations by language code, e.g. "en", "ko"
     translation?: string; // For backward compatibility if needed, though lib.rs dicts strict shape, but frontend code might use it?
+    words?: LyricWord[] | null; // Word/syllable timing for karaoke highlighting
+    singer?: string | null; // Vocalist/agent id for duets, e.g. "v1", "v2"
+    background?: BackgroundVocals | null; // Backing vocals sung alongside the line
+    ruby?: RubySegment[] | null; // text split into segments, with readings to draw above annotated ones
+    script?: Script | null; // Dominant script of text
+    direction?: TextDirection | null; // Base direction of text
+    pronDirection?: TextDirection | null;
+    transDirection?: TextDirection | null;
+}
+
+export type Script =
+    | "latin"
+    | "greek"
+    | "cyrillic"
+    | "armenian"
+    | "georgian"
+    | "hebrew"
+    | "arabic"
+    | "devanagari"
+    | "bengali"
+    | "tamil"
+    | "thai"
+    | "lao"
+    | "khmer"
+    | "hangul"
+    | "kana" // Japanese, including kanji in Japanese songs
+    | "han";
+
+export type TextDirection = "ltr" | "rtl";
+
+export interface BackgroundVocals {
+    text: string;
+    words?: LyricWord[] | null;
+}
+
+export interface RubySegment {
+    text: string;
+    reading?: string | null; // Furigana or pinyin shown above text; null for plain runs
+}
+
+export interface LyricWord {
+    startTime: number;
+    endTime?: number | null;
+    text: string;
 }
 
 export interface LyricsData {
     track: TrackInfo;
     lyrics: LyricLine[];
     isSynced: boolean;
+    script?: Script | null; // Dominant script across all lines
+    direction?: TextDirection | null;
+    translationLanguages?: string[] | null; // Languages kept in lines' translations, most preferred first
 }
 
 export interface NextTrackInfo {
@@ -39,6 +88,37 @@ export interface LyricsEvent {
     lyricsData: LyricsData;
 }
 
+export interface LyricsStatusEvent {
+    source: "local" | "cache";
+    fileName?: string | null;
+}
+
+export interface WordProgress {
+    lineIndex: number;
+    wordIndex: number;
+    progress: number; // 0-1 through the active word
+}
+
 export interface ProgressEvent {
     progressData: ProgressData;
+    wordProgress?: WordProgress | null;
+}
+
+// Payload of the "interlude" event; null once the break is over
+export interface Interlude {
+    startTime: number;
+    endTime: number;
+    remaining: number; // ms until the next line
+    nextLineIndex: number;
+}
+
+// Payload of the "translations-update" event: machine translations that arrived after "lyrics-update"
+export interface TranslationsEvent {
+    targetLanguage: string;
+    lines: {
+        lineIndex: number;
+        transText: string;
+        transDirection?: TextDirection | null;
+    }[];
+    profanityMasked: boolean; // The track's flag after these lines
 }